@@ -10,6 +10,11 @@ mod m20241202_000004_create_views_table;
 mod m20241226_000001_create_data_tables_table;
 mod m20241226_000002_create_data_table_columns_table;
 mod m20241226_000003_create_data_table_usages_table;
+mod m20251018_000001_create_task_schedules_table;
+mod m20251018_000002_create_task_runs_table;
+mod m20251020_000001_add_cache_ttl_to_views;
+mod m20251021_000001_create_view_versions_table;
+mod m20251027_000001_add_tenant_id_to_task_runs;
 
 pub struct Migrator;
 
@@ -27,6 +32,11 @@ impl MigratorTrait for Migrator {
             Box::new(m20241226_000001_create_data_tables_table::Migration),
             Box::new(m20241226_000002_create_data_table_columns_table::Migration),
             Box::new(m20241226_000003_create_data_table_usages_table::Migration),
+            Box::new(m20251018_000001_create_task_schedules_table::Migration),
+            Box::new(m20251018_000002_create_task_runs_table::Migration),
+            Box::new(m20251020_000001_add_cache_ttl_to_views::Migration),
+            Box::new(m20251021_000001_create_view_versions_table::Migration),
+            Box::new(m20251027_000001_add_tenant_id_to_task_runs::Migration),
         ]
     }
 }
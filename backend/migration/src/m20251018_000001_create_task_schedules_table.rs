@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 定时任务触发记录：每个任务一条，记录最近一次触发时间
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskSchedules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskSchedules::TaskId)
+                            .string_len(72)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskSchedules::Cron)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskSchedules::LastFiredAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskSchedules::LastLogicalDate)
+                            .string_len(8)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskSchedules::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TaskSchedules::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskSchedules::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskSchedules {
+    Table,
+    TaskId,
+    Cron,
+    LastFiredAt,
+    LastLogicalDate,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,120 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskRuns::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskRuns::Id)
+                            .string_len(72)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::TaskId)
+                            .string_len(72)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::TaskType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::LogicalDate)
+                            .string_len(8)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::Config)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::Status)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::Message)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::Data)
+                            .json()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::StartedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::FinishedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建索引：按任务查询运行历史
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_runs_task_id_started_at")
+                    .table(TaskRuns::Table)
+                    .col(TaskRuns::TaskId)
+                    .col(TaskRuns::StartedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建索引：按开始时间查询
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_runs_started_at")
+                    .table(TaskRuns::Table)
+                    .col(TaskRuns::StartedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskRuns::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskRuns {
+    Table,
+    Id,
+    TaskId,
+    TaskType,
+    LogicalDate,
+    Config,
+    Status,
+    Message,
+    Data,
+    StartedAt,
+    FinishedAt,
+    CreatedAt,
+}
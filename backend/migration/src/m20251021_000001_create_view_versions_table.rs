@@ -0,0 +1,116 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 视图的历史版本，每次修改 view_sql 或 view_type 时新增一行，不再修改
+        manager
+            .create_table(
+                Table::create()
+                    .table(ViewVersions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ViewVersions::Id)
+                            .string_len(80)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::ViewId)
+                            .string_len(72)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::TenantId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::ViewCode)
+                            .string_len(100)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::Version)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::ViewType)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::ViewSql)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::Author)
+                            .string_len(72)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::RestoredFrom)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ViewVersions::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建唯一索引：view_id + version
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_view_versions_view_id_version")
+                    .table(ViewVersions::Table)
+                    .col(ViewVersions::ViewId)
+                    .col(ViewVersions::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有视图的当前定义记为版本 1
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO view_versions (id, view_id, tenant_id, view_code, version, view_type, view_sql, created_at) \
+                 SELECT CONCAT(id, '-v1'), id, tenant_id, view_code, 1, view_type, view_sql, updated_at FROM views",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ViewVersions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ViewVersions {
+    Table,
+    Id,
+    ViewId,
+    TenantId,
+    ViewCode,
+    Version,
+    ViewType,
+    ViewSql,
+    Author,
+    RestoredFrom,
+    CreatedAt,
+}
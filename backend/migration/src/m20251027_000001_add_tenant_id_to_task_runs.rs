@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 运行所属租户，为空表示不属于任何租户（如任务中心未指定租户的定时任务）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TaskRuns::Table)
+                    .add_column(
+                        ColumnDef::new(TaskRuns::TenantId)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建索引：按租户和业务日期查询
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_runs_tenant_id_logical_date")
+                    .table(TaskRuns::Table)
+                    .col(TaskRuns::TenantId)
+                    .col(TaskRuns::LogicalDate)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_task_runs_tenant_id_logical_date")
+                    .table(TaskRuns::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TaskRuns::Table)
+                    .drop_column(TaskRuns::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskRuns {
+    Table,
+    TenantId,
+    LogicalDate,
+}
@@ -76,6 +76,7 @@ mod tests {
             id: user_id,
             nickname: nickname.clone(),
             password_hash,
+            tenant_id: Uuid::new_v4().to_string(),
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
         };
@@ -12,7 +12,7 @@ use std::sync::Arc;
 
 use crate::domain::{
     AuthService, DataSourceService,
-    UpdateDataSourceRequest, ServiceError,
+    UpdateDataSourceRequest, ServiceError, RowFormat,
 };
 
 // AppState 应用状态
@@ -163,6 +163,9 @@ async fn delete_handler(
 #[derive(Debug, Deserialize)]
 struct ExecuteSqlRequest {
     pub sql: String,
+    // 小数和时间戳的输出格式
+    #[serde(default)]
+    pub format: RowFormat,
 }
 
 // SQL 查询响应结构
@@ -180,7 +183,7 @@ async fn execute_sql_handler(
     Path(id): Path<String>,
     Json(payload): Json<ExecuteSqlRequest>,
 ) -> Result<Json<ExecuteSqlResponse>, ServiceError> {
-    let result = state.data_source_service.execute_sql(&id, &payload.sql).await?;
+    let result = state.data_source_service.execute_sql(&id, &payload.sql, &payload.format).await?;
     
     Ok(Json(ExecuteSqlResponse {
         columns: result.columns,
@@ -147,6 +147,7 @@ async fn upload_data_handler(
     mut multipart: Multipart,
 ) -> Result<Json<UploadResponse>, ServiceError> {
     let mut file_data: Option<Vec<u8>> = None;
+    let mut file_name: Option<String> = None;
     let mut partition_values: HashMap<String, String> = HashMap::new();
 
     // 解析 multipart 表单数据
@@ -158,6 +159,8 @@ async fn upload_data_handler(
             .to_string();
 
         if name == "file" {
+            // 文件名用于识别无法按内容判断的表格格式
+            file_name = field.file_name().map(|n| n.to_string());
             // 读取文件数据
             let data = field.bytes().await
                 .map_err(|e| ServiceError::InvalidInput(format!("Failed to read file data: {}", e)))?;
@@ -178,7 +181,7 @@ async fn upload_data_handler(
 
     // 调用服务层处理上传
     let rows_inserted = state.data_table_service
-        .upload_data(id, file_data, partition_values)
+        .upload_data(id, file_data, file_name, partition_values)
         .await?;
 
     Ok(Json(UploadResponse {
@@ -1,11 +1,11 @@
 use axum::{
     body::Body,
-    extract::State,
+    extract::{Path, Query, State},
     http::{Request, StatusCode},
     middleware::{self, Next},
-    response::Response,
-    Json, Router,
-    routing::post,
+    response::{IntoResponse, Response},
+    Extension, Json, Router,
+    routing::{get, post},
 };
 use serde::Deserialize;
 use std::sync::Arc;
@@ -13,7 +13,12 @@ use std::sync::Arc;
 use crate::domain::{
     AuthService, ExecutorEngine, ExecutorError,
     TaskMetadata, TaskType, ExecutionResult,
+    BackfillRequest, BackfillResult,
+    TaskRunService, TaskRunQuery, TaskRunDetail, ServiceError, AppError,
+    ExecutorInfo,
 };
+use crate::domain::auth::UserClaims;
+use crate::entities::task_run;
 
 // ExecuteRequest 执行请求结构
 #[derive(Debug, Deserialize)]
@@ -22,10 +27,18 @@ pub struct ExecuteRequest {
     pub metadata: TaskMetadata,
 }
 
+// ExecuteParams 执行参数，async=true 时立即返回运行记录
+#[derive(Debug, Default, Deserialize)]
+pub struct ExecuteParams {
+    #[serde(default, rename = "async")]
+    pub run_async: bool,
+}
+
 // AppState 应用状态
 #[derive(Clone)]
 pub struct ExecutorAppState {
     pub executor_engine: Arc<ExecutorEngine>,
+    pub task_run_service: Arc<TaskRunService>,
     pub auth_service: Arc<AuthService>,
 }
 
@@ -62,30 +75,117 @@ async fn jwt_auth_middleware(
 }
 
 // 执行任务处理函数
+// curl -v "$TARGET/api/v1/executor/execute?async=true" -H 'Content-Type: application/json' -H "Authorization: Bearer $TOKEN" -d '{ "task_type": "excel", "metadata": { ... } }'
 async fn execute_handler(
     State(state): State<ExecutorAppState>,
-    Json(payload): Json<ExecuteRequest>,
-) -> Result<Json<ExecutionResult>, ExecutorError> {
-    let result = state
+    Query(params): Query<ExecuteParams>,
+    Extension(claims): Extension<UserClaims>,
+    Json(mut payload): Json<ExecuteRequest>,
+) -> Result<Response, ExecutorError> {
+    payload.metadata.tenant_id = Some(claims.tenant_id);
+
+    // 异步模式：排队执行，返回运行记录，通过 GET /runs/{id} 轮询
+    if params.run_async {
+        let run = state
+            .executor_engine
+            .submit_task(payload.task_type, payload.metadata)
+            .await?;
+
+        return Ok((StatusCode::ACCEPTED, Json(run)).into_response());
+    }
+
+    let result: ExecutionResult = state
         .executor_engine
         .execute_task(payload.task_type, payload.metadata)
         .await?;
     
+    Ok(Json(result).into_response())
+}
+
+// 回填任务处理函数
+// curl -v "$TARGET/api/v1/executor/backfill" -H 'Content-Type: application/json' -H "Authorization: Bearer $TOKEN" -d '{ "task_type": "sql", "metadata": { "task_id": "dwd_rival_stats_di_1d", "config": { ... } }, "start_date": "20251001", "end_date": "20251026", "parallelism": 2, "continue_on_error": false }'
+async fn backfill_handler(
+    State(state): State<ExecutorAppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(mut payload): Json<BackfillRequest>,
+) -> Result<Json<BackfillResult>, ExecutorError> {
+    payload.metadata.tenant_id = Some(claims.tenant_id);
+
+    let result = state
+        .executor_engine
+        .backfill(payload.task_type, payload.metadata, payload.options)
+        .await?;
+
     Ok(Json(result))
 }
 
+// 执行器列表处理函数
+async fn list_executors_handler(
+    State(state): State<ExecutorAppState>,
+) -> Json<Vec<ExecutorInfo>> {
+    Json(state.executor_engine.executors())
+}
+
+// 运行历史列表处理函数，仅返回当前租户的运行
+// curl -v "$TARGET/api/v1/executor/runs?task_id=dwd_rival_stats_di_1d&status=failed&logical_date_from=20251001&logical_date_to=20251026" -H "Authorization: Bearer $TOKEN"
+async fn list_runs_handler(
+    State(state): State<ExecutorAppState>,
+    Query(query): Query<TaskRunQuery>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<Vec<task_run::Model>>, ServiceError> {
+    let runs = state.task_run_service.list(&claims.tenant_id, query).await?;
+    Ok(Json(runs))
+}
+
+// 运行详情处理函数
+async fn get_run_handler(
+    State(state): State<ExecutorAppState>,
+    Path(id): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<TaskRunDetail>, ServiceError> {
+    let run = state.task_run_service.get(&claims.tenant_id, &id).await?;
+    Ok(Json(run))
+}
+
+// 取消运行处理函数
+// curl -v -X POST "$TARGET/api/v1/executor/runs/$RUN_ID/cancel" -H "Authorization: Bearer $TOKEN"
+async fn cancel_run_handler(
+    State(state): State<ExecutorAppState>,
+    Path(id): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<TaskRunDetail>, AppError> {
+    let run = state.task_run_service.get(&claims.tenant_id, &id).await?;
+    if run.run.finished_at.is_some() {
+        return Err(ServiceError::InvalidInput(format!("Run {} has already finished", id)).into());
+    }
+
+    if !state.executor_engine.cancel_run(&id).await {
+        return Err(ServiceError::InvalidInput(format!("Run {} is not in progress on this instance", id)).into());
+    }
+
+    let run = state.task_run_service.get(&claims.tenant_id, &id).await?;
+    Ok(Json(run))
+}
+
 // 创建执行器路由
 pub fn create_executor_routes(
     executor_engine: Arc<ExecutorEngine>,
+    task_run_service: Arc<TaskRunService>,
     auth_service: Arc<AuthService>,
 ) -> Router {
     let state = ExecutorAppState {
         executor_engine,
+        task_run_service,
         auth_service,
     };
 
     Router::new()
         .route("/execute", post(execute_handler))
+        .route("/backfill", post(backfill_handler))
+        .route("/executors", get(list_executors_handler))
+        .route("/runs", get(list_runs_handler))
+        .route("/runs/{id}", get(get_run_handler))
+        .route("/runs/{id}/cancel", post(cancel_run_handler))
         .layer(middleware::from_fn_with_state(state.clone(), jwt_auth_middleware))
         .with_state(state)
 }
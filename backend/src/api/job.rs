@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Path, State},
+    middleware,
+    Extension, Json, Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+use crate::api::middleware::{jwt_auth_middleware, AuthMiddlewareState};
+use crate::domain::{
+    AuthService, JobService, JobDefinition, RunJobRequest, BackfillJobRequest,
+    BackfillResult, ExecutionResult, ServiceError, AppError,
+};
+use crate::domain::auth::UserClaims;
+
+// AppState 应用状态
+#[derive(Clone)]
+pub struct JobAppState {
+    pub job_service: Arc<JobService>,
+}
+
+// 列表查询处理函数
+async fn list_handler(
+    State(state): State<JobAppState>,
+) -> Result<Json<Vec<JobDefinition>>, ServiceError> {
+    let jobs = state.job_service.list().await?;
+    Ok(Json(jobs))
+}
+
+// 详情查询处理函数
+async fn get_handler(
+    State(state): State<JobAppState>,
+    Path(name): Path<String>,
+) -> Result<Json<JobDefinition>, ServiceError> {
+    let job = state.job_service.get(&name).await?;
+    Ok(Json(job))
+}
+
+// 执行作业处理函数（租户取自令牌）
+// curl -v "$TARGET/api/v1/jobs/dwd_rival_stats_di_1d/run" -H 'Content-Type: application/json' -H "Authorization: Bearer $TOKEN" -d '{ "logical_date": "20251026" }'
+async fn run_handler(
+    State(state): State<JobAppState>,
+    Path(name): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+    payload: Option<Json<RunJobRequest>>,
+) -> Result<Json<ExecutionResult>, AppError> {
+    let req = payload.map(|Json(req)| req).unwrap_or_default();
+    let result = state.job_service.run(&name, &claims.tenant_id, req).await?;
+    Ok(Json(result))
+}
+
+// 回填作业处理函数（租户取自令牌）
+// curl -v "$TARGET/api/v1/jobs/dwd_rival_stats_di_1d/backfill" -H 'Content-Type: application/json' -H "Authorization: Bearer $TOKEN" -d '{ "start_date": "20251001", "end_date": "20251026", "parallelism": 2 }'
+async fn backfill_handler(
+    State(state): State<JobAppState>,
+    Path(name): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+    Json(payload): Json<BackfillJobRequest>,
+) -> Result<Json<BackfillResult>, AppError> {
+    let result = state.job_service.backfill(&name, &claims.tenant_id, payload).await?;
+    Ok(Json(result))
+}
+
+// 创建作业路由
+pub fn create_job_routes(
+    job_service: Arc<JobService>,
+    auth_service: Arc<AuthService>,
+) -> Router {
+    let state = JobAppState { job_service };
+    let auth_state = AuthMiddlewareState { auth_service };
+
+    Router::new()
+        .route("/", get(list_handler))
+        .route("/{name}", get(get_handler))
+        .route("/{name}/run", post(run_handler))
+        .route("/{name}/backfill", post(backfill_handler))
+        .layer(middleware::from_fn_with_state(auth_state, jwt_auth_middleware))
+        .with_state(state)
+}
@@ -78,6 +78,13 @@ pub async fn logging_middleware(
 // 统一错误处理 - 将AppError转换为HTTP响应
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let details = match &self {
+            AppError::Executor(ExecutorError::DependenciesNotSatisfied(dependencies)) => {
+                Some(serde_json::json!({ "dependencies": dependencies }))
+            }
+            _ => None,
+        };
+
         let (status, code, message) = match self {
             AppError::Auth(auth_err) => match auth_err {
                 AuthError::InvalidCredentials => (
@@ -132,6 +139,16 @@ impl IntoResponse for AppError {
                     "INVALID_INPUT",
                     msg.as_str(),
                 ),
+                ServiceError::Conflict(msg) => (
+                    StatusCode::CONFLICT,
+                    "CONFLICT",
+                    msg.as_str(),
+                ),
+                ServiceError::DatabaseError(_) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "DATABASE_ERROR",
+                    "Database operation failed",
+                ),
             },
             AppError::Database(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -154,6 +171,36 @@ impl IntoResponse for AppError {
                     "CONNECTION_FAILED",
                     "Data source connection failed",
                 ),
+                ExecutorError::DependenciesNotSatisfied(_) => (
+                    StatusCode::CONFLICT,
+                    "DEPENDENCIES_NOT_SATISFIED",
+                    "Upstream dependencies have not completed for this logical date",
+                ),
+                ExecutorError::Cancelled => (
+                    StatusCode::CONFLICT,
+                    "CANCELLED",
+                    "Run was cancelled",
+                ),
+                ExecutorError::QueueFull(_) => (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "QUEUE_FULL",
+                    "Too many runs are waiting, retry later",
+                ),
+                ExecutorError::Timeout(_) => (
+                    StatusCode::GATEWAY_TIMEOUT,
+                    "TIMEOUT",
+                    "Task execution timed out",
+                ),
+                ExecutorError::DownloadFailed(msg) => (
+                    StatusCode::BAD_GATEWAY,
+                    "DOWNLOAD_FAILED",
+                    msg.as_str(),
+                ),
+                ExecutorError::UploadFailed(msg) => (
+                    StatusCode::BAD_GATEWAY,
+                    "UPLOAD_FAILED",
+                    msg.as_str(),
+                ),
             },
             AppError::Client(ref client_err) => match client_err {
                 ClientError::RequestFailed(msg) => (
@@ -172,7 +219,7 @@ impl IntoResponse for AppError {
         let error_response = ErrorResponse {
             code: code.to_string(),
             message: message.to_string(),
-            details: None,
+            details,
         };
 
         (status, Json(error_response)).into_response()
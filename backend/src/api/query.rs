@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
     Json, Router,
     routing::post,
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
 
-use crate::domain::{QueryService, QueryError};
+use crate::domain::{QueryService, QueryError, RowFormat};
 
 #[derive(Debug, Deserialize)]
 pub struct QueryRequest {
@@ -20,6 +20,16 @@ pub struct QueryRequest {
 #[derive(Debug, Deserialize)]
 pub struct ViewQueryRequest {
     pub params: Value,
+    // Rendering of decimals and timestamps in the result rows
+    #[serde(default)]
+    pub format: RowFormat,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewQueryParams {
+    // Query an earlier version of the view instead of the current one
+    #[serde(default)]
+    pub version: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -60,9 +70,11 @@ async fn query_handler(
 // New view-based query endpoint (SQL stored in database):
 // curl -v http://localhost:8080/api/v1/views/card_tx_amount/query -H 'Content-Type: application/json' -H 'tenant_id: 1' -d '{ "params": { "start": "0101", "end": "1231" } }'
 // curl -v http://localhost:8080/api/v1/views/card_tx_amount/query -H 'Content-Type: application/json' -H 'tenant_id: 1' -d '{ "params": { "start": "0101", "end": "1231", "category_level1": "家居日用" } }'
+// curl -v 'http://localhost:8080/api/v1/views/card_tx_amount/query?version=2' -H 'Content-Type: application/json' -H 'tenant_id: 1' -d '{ "params": { "start": "0101", "end": "1231" } }'
 async fn view_query_handler(
     State(state): State<QueryAppState>,
     Path(view_code): Path<String>,
+    Query(query): Query<ViewQueryParams>,
     headers: HeaderMap,
     Json(payload): Json<ViewQueryRequest>,
 ) -> Result<Json<QueryResponse>, QueryError> {
@@ -76,7 +88,7 @@ async fn view_query_handler(
     // Execute query by view code
     let result = state
         .query_service
-        .execute_query_by_view_code(&tenant_id, &view_code, payload.params)
+        .execute_query_by_view_code(&tenant_id, &view_code, payload.params, payload.format, query.version)
         .await?;
 
     Ok(Json(QueryResponse { data: result }))
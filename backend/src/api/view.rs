@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    Extension, Json, Router,
+    routing::{get, post, put, delete},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::api::middleware::{jwt_auth_middleware, AuthMiddlewareState};
+use crate::domain::auth::UserClaims;
+use crate::domain::{AuthService, ViewService, CreateViewRequest, UpdateViewRequest, ServiceError};
+
+#[derive(Clone)]
+pub struct ViewAppState {
+    pub view_service: Arc<ViewService>,
+}
+
+// 回滚请求结构
+#[derive(Debug, Deserialize)]
+struct RollbackRequest {
+    version: i32,
+}
+
+/*
+curl -v "$TARGET/api/v1/views" \
+-H "Authorization: Bearer $TOKEN"
+*/
+// 列表查询处理函数 - 按租户
+async fn list_handler(
+    State(state): State<ViewAppState>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<Vec<crate::entities::view::Model>>, ServiceError> {
+    let views = state.view_service.list_by_tenant(&claims.tenant_id).await?;
+    Ok(Json(views))
+}
+
+/*
+curl -v "$TARGET/api/v1/views" \
+-H 'Content-Type: application/json' \
+-H "Authorization: Bearer $TOKEN" \
+-d '
+{
+    "view_code": "dropdown_shops",
+    "view_type": "comparable_card",
+    "view_sql": "SELECT DISTINCT shop_name option_key, shop_name option_value FROM dwd_rival_stats_distincted_di_1d WHERE date_str = {end} [shop_owner:AND shop_owner = {shop_owner}] ORDER BY shop_name"
+}
+'
+*/
+// 创建处理函数，保存前校验视图类型并在数据源上预编译SQL
+async fn create_handler(
+    State(state): State<ViewAppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(mut payload): Json<CreateViewRequest>,
+) -> Result<(StatusCode, Json<crate::entities::view::Model>), ServiceError> {
+    payload.tenant_id = Some(claims.tenant_id.clone());
+    payload.author = Some(claims.user_id.clone());
+    let view = state.view_service.create(payload).await?;
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+// 详情查询处理函数
+async fn get_handler(
+    State(state): State<ViewAppState>,
+    Path(view_code): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<crate::entities::view::Model>, ServiceError> {
+    let view = state.view_service.get(&claims.tenant_id, &view_code).await?;
+    Ok(Json(view))
+}
+
+// 更新处理函数，view_sql 或 view_type 变化时记录新版本
+async fn update_handler(
+    State(state): State<ViewAppState>,
+    Path(view_code): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+    Json(payload): Json<UpdateViewRequest>,
+) -> Result<Json<crate::entities::view::Model>, ServiceError> {
+    let view = state.view_service
+        .update(&claims.tenant_id, &view_code, payload, &claims.user_id)
+        .await?;
+    Ok(Json(view))
+}
+
+// 版本列表查询处理函数，按版本号倒序
+async fn list_versions_handler(
+    State(state): State<ViewAppState>,
+    Path(view_code): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<Vec<crate::entities::view_version::Model>>, ServiceError> {
+    let versions = state.view_service.list_versions(&claims.tenant_id, &view_code).await?;
+    Ok(Json(versions))
+}
+
+// 版本详情查询处理函数
+async fn get_version_handler(
+    State(state): State<ViewAppState>,
+    Path((view_code, version)): Path<(String, i32)>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<crate::entities::view_version::Model>, ServiceError> {
+    let version = state.view_service.get_version(&claims.tenant_id, &view_code, version).await?;
+    Ok(Json(version))
+}
+
+/*
+curl -v "$TARGET/api/v1/views/dropdown_shops/rollback" \
+-H 'Content-Type: application/json' \
+-H "Authorization: Bearer $TOKEN" \
+-d '{ "version": 2 }'
+*/
+// 回滚处理函数，恢复指定版本的定义并记为新版本
+async fn rollback_handler(
+    State(state): State<ViewAppState>,
+    Path(view_code): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+    Json(payload): Json<RollbackRequest>,
+) -> Result<Json<crate::entities::view::Model>, ServiceError> {
+    let view = state.view_service
+        .rollback(&claims.tenant_id, &view_code, payload.version, &claims.user_id)
+        .await?;
+    Ok(Json(view))
+}
+
+// 删除处理函数
+async fn delete_handler(
+    State(state): State<ViewAppState>,
+    Path(view_code): Path<String>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<StatusCode, ServiceError> {
+    state.view_service.delete(&claims.tenant_id, &view_code).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 创建视图路由
+pub fn create_view_routes(
+    view_service: Arc<ViewService>,
+    auth_service: Arc<AuthService>,
+) -> Router {
+    let state = ViewAppState { view_service };
+    let auth_state = AuthMiddlewareState { auth_service };
+
+    Router::new()
+        .route("/", get(list_handler))
+        .route("/", post(create_handler))
+        .route("/{view_code}", get(get_handler))
+        .route("/{view_code}", put(update_handler))
+        .route("/{view_code}", delete(delete_handler))
+        .route("/{view_code}/versions", get(list_versions_handler))
+        .route("/{view_code}/versions/{version}", get(get_version_handler))
+        .route("/{view_code}/rollback", post(rollback_handler))
+        .layer(middleware::from_fn_with_state(auth_state, jwt_auth_middleware))
+        .with_state(state)
+}
@@ -25,6 +25,75 @@ pub struct TaskCenterConfig {
     pub timeout_seconds: u64,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct ExecutorConfig {
+    // Number of submitted runs executed at once, further runs wait in the queue
+    pub max_concurrent_runs: usize,
+    // Submitted runs waiting for a worker, further submissions are rejected
+    pub max_queued_runs: usize,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self { max_concurrent_runs: 4, max_queued_runs: 100 }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PoolConfig {
+    // Connections per data source, unless its connection config sets `max_connections`
+    pub max_connections: u32,
+    // Pools (and pooled connections) unused for this long are closed
+    pub idle_timeout_seconds: u64,
+    // How long a request waits for a free connection
+    pub acquire_timeout_seconds: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self { max_connections: 5, idle_timeout_seconds: 600, acquire_timeout_seconds: 30 }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CacheConfig {
+    // Redis holding cached view query results, kept in memory when unset
+    pub redis_url: Option<String>,
+    // Seconds results are cached for views without their own TTL, 0 disables caching
+    pub default_ttl_seconds: u64,
+    // Results kept by the in-memory cache
+    pub max_entries: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { redis_url: None, default_ttl_seconds: 300, max_entries: 1000 }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JobsConfig {
+    pub dir: String,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self { dir: "jobs".to_string() }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    pub poll_interval_seconds: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self { enabled: true, poll_interval_seconds: 30 }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct LoggingConfig {
     pub level: String,
@@ -36,6 +105,16 @@ pub struct AppConfig {
     pub database: DatabaseConfig,
     pub jwt: JwtConfig,
     pub task_center: TaskCenterConfig,
+    #[serde(default)]
+    pub executor: ExecutorConfig,
+    #[serde(default)]
+    pub pools: PoolConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub jobs: JobsConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
     pub logging: LoggingConfig,
 }
 
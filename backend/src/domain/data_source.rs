@@ -1,11 +1,15 @@
 use std::sync::Arc;
 use sea_orm::*;
+use sqlx::Connection;
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
 use crate::entities::data_source;
 use crate::repository::data_source::DataSourceRepository;
+use super::connection_pool::ConnectionPools;
 use super::error::ServiceError;
-use sqlx::{Row, Column};
+use super::executor::sql_script;
+use super::query::QueryCache;
+use super::query::rows::{column_names, row_values, RowFormat};
 use serde_json::Value;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,11 +30,22 @@ pub struct UpdateDataSourceRequest {
 
 pub struct DataSourceService {
     repo: Arc<DataSourceRepository>,
+    pools: Arc<ConnectionPools>,
+    query_cache: Arc<QueryCache>,
 }
 
 impl DataSourceService {
-    pub fn new(repo: Arc<DataSourceRepository>) -> Self {
-        Self { repo }
+    pub fn new(
+        repo: Arc<DataSourceRepository>,
+        pools: Arc<ConnectionPools>,
+        query_cache: Arc<QueryCache>,
+    ) -> Self {
+        Self { repo, pools, query_cache }
+    }
+
+    /// Connection pools shared by the users of data sources
+    pub fn connection_pools(&self) -> Arc<ConnectionPools> {
+        self.pools.clone()
     }
 
     pub async fn create(&self, req: CreateDataSourceRequest) -> Result<data_source::Model, ServiceError> {
@@ -95,8 +110,13 @@ impl DataSourceService {
             updated_at: Set(now),
         };
 
-        self.repo.update(model).await
-            .map_err(|_| ServiceError::InvalidInput("Failed to update data source".to_string()))
+        let updated = self.repo.update(model).await
+            .map_err(|_| ServiceError::InvalidInput("Failed to update data source".to_string()))?;
+
+        // 连接配置可能已变更，丢弃旧连接池
+        self.pools.invalidate(&updated.id);
+
+        Ok(updated)
     }
 
     pub async fn delete(&self, id: String) -> Result<(), ServiceError> {
@@ -108,99 +128,85 @@ impl DataSourceService {
         self.repo.delete(&existing.id).await
             .map_err(|_| ServiceError::InvalidInput("Failed to delete data source".to_string()))?;
 
+        self.pools.invalidate(&existing.id);
+
         Ok(())
     }
 
-    pub async fn execute_sql(&self, id: &str, sql: &str) -> Result<SqlExecutionResult, ServiceError> {
+    pub async fn execute_sql(
+        &self,
+        id: &str,
+        sql: &str,
+        format: &RowFormat,
+    ) -> Result<SqlExecutionResult, ServiceError> {
+        format.validate().map_err(ServiceError::InvalidInput)?;
+
         // Get the data source
         let data_source = self.repo.find_by_id(id).await
             .map_err(|_| ServiceError::InvalidInput("Failed to find data source".to_string()))?
             .ok_or(ServiceError::NotFound)?;
 
         // Execute SQL based on database type
-        match data_source.db_type.as_str() {
+        let result = match data_source.db_type.as_str() {
             "postgresql" | "PostgreSQL" => {
-                self.execute_postgresql_query(sql, &data_source.connection_config).await
+                self.execute_postgresql_query(sql, &data_source, format).await
             }
-            _ => Err(ServiceError::InvalidInput(
+            _ => return Err(ServiceError::InvalidInput(
                 format!("Unsupported database type: {}. Only PostgreSQL is supported.", data_source.db_type)
             )),
+        };
+
+        // Query results cached from the written tables are stale, whether or not the SQL succeeded
+        let tables: Vec<String> = sql_script::split_statements(sql)
+            .iter()
+            .filter_map(|statement| sql_script::written_table(statement))
+            .collect();
+        if !tables.is_empty() {
+            self.query_cache.invalidate_tables(&data_source.tenant_id, &tables).await;
         }
+
+        result
     }
 
     async fn execute_postgresql_query(
         &self,
         sql: &str,
-        connection_config: &serde_json::Value,
+        data_source: &data_source::Model,
+        format: &RowFormat,
     ) -> Result<SqlExecutionResult, ServiceError> {
-        // Parse connection config
-        let config = connection_config.as_object()
-            .ok_or_else(|| ServiceError::InvalidInput("Invalid connection config".to_string()))?;
-
-        let host = config.get("host")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ServiceError::InvalidInput("Missing host in connection config".to_string()))?;
-        
-        let port = config.get("port")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(5432);
-        
-        let database = config.get("database")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ServiceError::InvalidInput("Missing database in connection config".to_string()))?;
-        
-        let username = config.get("username")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ServiceError::InvalidInput("Missing username in connection config".to_string()))?;
-        
-        let password = config.get("password")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ServiceError::InvalidInput("Missing password in connection config".to_string()))?;
-
-        // Build connection string
-        let connection_string = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            username, password, host, port, database
-        );
-
-        // Create connection pool
-        let pool = sqlx::postgres::PgPoolOptions::new()
-            .max_connections(5)
-            .connect(&connection_string)
-            .await
-            .map_err(|e| ServiceError::InvalidInput(format!("Failed to connect to database: {}", e)))?;
-
-        // Execute query
-        let rows = sqlx::query(sql)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| ServiceError::InvalidInput(format!("Failed to execute SQL: {}", e)))?;
-
-        // Extract column names and data
-        let mut columns = Vec::new();
-        let mut result_rows = Vec::new();
+        // Shared connection pool of the data source
+        let pool = self.pools.postgres(data_source).await?;
+        let mut conn = pool.acquire().await
+            .map_err(|e| ServiceError::InvalidInput(format!("Failed to connect to data source: {}", e)))?;
 
-        if let Some(first_row) = rows.first() {
-            for column in first_row.columns() {
-                columns.push(column.name().to_string());
-            }
+        // Execute query, without caching the ad-hoc statement on the connection
+        let rows = sqlx::query(sql)
+            .persistent(false)
+            .fetch_all(&mut *conn)
+            .await;
+
+        // The SQL may have changed session state (search_path, statement_timeout, temporary
+        // tables, an open transaction...) that must not leak to later users of the connection
+        let reset = match conn.clear_cached_statements().await {
+            Ok(()) => sqlx::query("DISCARD ALL").persistent(false).execute(&mut *conn).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = reset {
+            log::warn!("Closing connection to data source {} that could not be reset: {}", data_source.id, e);
+            conn.close_on_drop();
         }
 
-        for row in rows {
-            let mut result_row = Vec::new();
-            for (i, _column) in columns.iter().enumerate() {
-                let value: Option<String> = row.try_get(i).ok();
-                result_row.push(match value {
-                    Some(v) => Value::String(v),
-                    None => Value::Null,
-                });
-            }
-            result_rows.push(result_row);
-        }
+        let rows = rows.map_err(|e| ServiceError::InvalidInput(format!("Failed to execute SQL: {}", e)))?;
 
-        let row_count = result_rows.len();
+        // Extract column names and data
+        let columns = rows.first().map(column_names).unwrap_or_default();
+        let result_rows = rows
+            .iter()
+            .map(|row| row_values(row, format))
+            .collect::<Result<Vec<Vec<Value>>, _>>()
+            .map_err(ServiceError::InvalidInput)?;
 
-        pool.close().await;
+        let row_count = result_rows.len();
 
         Ok(SqlExecutionResult {
             columns,
@@ -3,13 +3,15 @@ use std::collections::HashMap;
 use sea_orm::*;
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
-use calamine::{Reader, open_workbook_from_rs, Xlsx};
-use std::io::Cursor;
+use calamine::Reader;
 use crate::entities::{data_table, data_table_column, data_table_usage};
 use crate::repository::{
     DataTableRepository, DataTableColumnRepository, DataTableUsageRepository, DataSourceRepository,
 };
+use super::connection_pool::ConnectionPools;
 use super::error::ServiceError;
+use super::query::QueryCache;
+use super::workbook;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateDataTableRequest {
@@ -39,6 +41,8 @@ pub struct DataTableService {
     column_repo: Arc<DataTableColumnRepository>,
     usage_repo: Arc<DataTableUsageRepository>,
     data_source_repo: Arc<DataSourceRepository>,
+    connection_pools: Arc<ConnectionPools>,
+    query_cache: Arc<QueryCache>,
 }
 
 impl DataTableService {
@@ -47,15 +51,24 @@ impl DataTableService {
         column_repo: Arc<DataTableColumnRepository>,
         usage_repo: Arc<DataTableUsageRepository>,
         data_source_repo: Arc<DataSourceRepository>,
+        connection_pools: Arc<ConnectionPools>,
+        query_cache: Arc<QueryCache>,
     ) -> Self {
         Self {
             table_repo,
             column_repo,
             usage_repo,
             data_source_repo,
+            connection_pools,
+            query_cache,
         }
     }
 
+    /// Cache of view query results, dropped when data tables are written
+    pub fn query_cache(&self) -> Arc<QueryCache> {
+        self.query_cache.clone()
+    }
+
     pub async fn create(&self, req: CreateDataTableRequest) -> Result<data_table::Model, ServiceError> {
         if req.name.trim().is_empty() {
             return Err(ServiceError::InvalidInput("Name cannot be empty".to_string()));
@@ -171,6 +184,7 @@ impl DataTableService {
         &self,
         table_id: String,
         file_data: Vec<u8>,
+        file_name: Option<String>,
         partition_values: HashMap<String, String>,
     ) -> Result<usize, ServiceError> {
         // 1. 获取数据表信息
@@ -199,9 +213,8 @@ impl DataTableService {
             }
         }
 
-        // 4. 解析 Excel 文件
-        let cursor = Cursor::new(file_data);
-        let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)
+        // 4. 解析 Excel 文件（按文件内容或文件名识别 xls、xlsx、xlsb、ods）
+        let mut workbook = workbook::open_workbook(&file_data, file_name.as_deref())
             .map_err(|e| ServiceError::InvalidInput(format!("Failed to open Excel file: {}", e)))?;
 
         // 获取第一个工作表
@@ -242,35 +255,9 @@ impl DataTableService {
             .map_err(|_| ServiceError::InvalidInput("Failed to find data source".to_string()))?
             .ok_or(ServiceError::NotFound)?;
 
-        // 解析连接配置
-        let connection_config = data_source.connection_config.as_object()
-            .ok_or(ServiceError::InvalidInput("Invalid connection config".to_string()))?;
-
-        let host = connection_config.get("host")
-            .and_then(|v| v.as_str())
-            .ok_or(ServiceError::InvalidInput("Missing host in connection config".to_string()))?;
-        let port = connection_config.get("port")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(5432);
-        let database = connection_config.get("database")
-            .and_then(|v| v.as_str())
-            .ok_or(ServiceError::InvalidInput("Missing database in connection config".to_string()))?;
-        let username = connection_config.get("username")
-            .and_then(|v| v.as_str())
-            .ok_or(ServiceError::InvalidInput("Missing username in connection config".to_string()))?;
-        let password = connection_config.get("password")
-            .and_then(|v| v.as_str())
-            .ok_or(ServiceError::InvalidInput("Missing password in connection config".to_string()))?;
-
-        // 构建连接字符串
-        let connection_string = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            username, password, host, port, database
-        );
-
-        // 连接到目标数据库
-        let target_db = Database::connect(&connection_string).await
-            .map_err(|e| ServiceError::InvalidInput(format!("Failed to connect to target database: {}", e)))?;
+        // 共享的数据源连接池
+        let target_db = self.connection_pools.get(&data_source).await?;
+        let backend = target_db.get_database_backend();
 
         // 7. 删除指定分区的现有数据
         let mut delete_conditions = Vec::new();
@@ -287,7 +274,7 @@ impl DataTableService {
         };
 
         target_db.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+            backend,
             delete_sql,
         )).await
             .map_err(|e| ServiceError::InvalidInput(format!("Failed to delete existing data: {}", e)))?;
@@ -345,7 +332,7 @@ impl DataTableService {
             );
 
             target_db.execute(Statement::from_string(
-                DatabaseBackend::Postgres,
+                backend,
                 insert_sql,
             )).await
                 .map_err(|e| ServiceError::InvalidInput(format!("Failed to insert data: {}", e)))?;
@@ -353,6 +340,9 @@ impl DataTableService {
             rows_inserted += 1;
         }
 
+        // 9. 清除基于该表的查询结果缓存
+        self.query_cache.invalidate_tables(&table.tenant_id, std::slice::from_ref(&table.name)).await;
+
         Ok(rows_inserted)
     }
 
@@ -52,6 +52,12 @@ pub enum ServiceError {
     
     #[error("Unauthorized")]
     Unauthorized,
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sea_orm::DbErr),
 }
 
 #[derive(Debug, Error)]
@@ -64,6 +70,69 @@ pub enum ExecutorError {
     
     #[error("Data source connection failed")]
     ConnectionFailed,
+
+    #[error("Dependencies not satisfied: {}", .0.join(", "))]
+    DependenciesNotSatisfied(Vec<String>),
+
+    #[error("Run cancelled")]
+    Cancelled,
+
+    #[error("Run queue is full ({0} runs waiting)")]
+    QueueFull(usize),
+
+    #[error("Task timed out after {0} seconds")]
+    Timeout(u64),
+
+    #[error("Download failed: {0}")]
+    DownloadFailed(String),
+
+    #[error("Upload failed: {0}")]
+    UploadFailed(String),
+}
+
+impl ExecutorError {
+    /// Error kind as named in retry policies
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExecutorError::UnsupportedTaskType => "unsupported_task_type",
+            ExecutorError::ExecutionFailed(_) => "execution_failed",
+            ExecutorError::ConnectionFailed => "connection_failed",
+            ExecutorError::DependenciesNotSatisfied(_) => "dependencies_not_satisfied",
+            ExecutorError::Cancelled => "cancelled",
+            ExecutorError::QueueFull(_) => "queue_full",
+            ExecutorError::Timeout(_) => "timeout",
+            ExecutorError::DownloadFailed(_) => "download_failed",
+            ExecutorError::UploadFailed(_) => "upload_failed",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PoolError {
+    #[error("Invalid connection config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Unsupported database type: {0}")]
+    UnsupportedType(String),
+
+    #[error("Failed to connect to {0}")]
+    ConnectFailed(String),
+}
+
+impl From<PoolError> for ExecutorError {
+    fn from(e: PoolError) -> Self {
+        match e {
+            // Connection errors are retryable, like any other failed connection
+            PoolError::ConnectFailed(_) => ExecutorError::ConnectionFailed,
+            other => ExecutorError::ExecutionFailed(other.to_string()),
+        }
+    }
+}
+
+impl From<PoolError> for ServiceError {
+    fn from(e: PoolError) -> Self {
+        ServiceError::InvalidInput(e.to_string())
+    }
 }
 
 #[derive(Debug, Error)]
@@ -0,0 +1,275 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::Duration;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use crate::domain::error::ExecutorError;
+use super::template::parse_logical_date;
+use super::{ExecutionResult, ExecutorEngine, TaskMetadata, TaskType};
+
+// Upper bound on the number of partitions of a single backfill
+const MAX_BACKFILL_DAYS: i64 = 366;
+
+// Upper bound on the number of partitions of a single backfill run concurrently
+const MAX_BACKFILL_PARALLELISM: usize = 16;
+
+// Backfill request - runs a task once per logical date in [start_date, end_date]
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillRequest {
+    pub task_type: TaskType,
+    pub metadata: TaskMetadata,
+    #[serde(flatten)]
+    pub options: BackfillOptions,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillOptions {
+    pub start_date: String,
+    pub end_date: String,
+    // Number of dates run concurrently, clamped to 1..=MAX_BACKFILL_PARALLELISM
+    #[serde(default = "default_parallelism")]
+    pub parallelism: usize,
+    // Keep running remaining dates after a failure
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+fn default_parallelism() -> usize {
+    1
+}
+
+impl BackfillOptions {
+    // Logical dates (yyyyMMdd) of the range, in ascending order
+    fn dates(&self) -> Result<Vec<String>, ExecutorError> {
+        let start = parse_logical_date(&self.start_date)?;
+        let end = parse_logical_date(&self.end_date)?;
+
+        if end < start {
+            return Err(ExecutorError::ExecutionFailed(
+                "Backfill end_date must not be before start_date".to_string()
+            ));
+        }
+        let days = (end - start).num_days() + 1;
+        if days > MAX_BACKFILL_DAYS {
+            return Err(ExecutorError::ExecutionFailed(
+                format!("Backfill range of {} days exceeds the limit of {}", days, MAX_BACKFILL_DAYS)
+            ));
+        }
+
+        Ok((0..days)
+            .map(|offset| (start + Duration::days(offset)).format("%Y%m%d").to_string())
+            .collect())
+    }
+
+    fn parallelism(&self) -> usize {
+        self.parallelism.clamp(1, MAX_BACKFILL_PARALLELISM)
+    }
+}
+
+// Backfill summary
+#[derive(Debug, Clone, Serialize)]
+pub struct BackfillResult {
+    pub success: bool,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub runs: Vec<BackfillRun>,
+}
+
+// Outcome of a single date partition
+#[derive(Debug, Clone, Serialize)]
+pub struct BackfillRun {
+    pub logical_date: String,
+    pub status: BackfillStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<ExecutionResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+impl ExecutorEngine {
+    /// Run a task once per logical date between `start_date` and `end_date` (inclusive).
+    ///
+    /// Dates are started in ascending order, at most `parallelism` at a time. Unless
+    /// `continue_on_error` is set, dates not yet started after a failure are skipped.
+    pub async fn backfill(
+        &self,
+        task_type: TaskType,
+        metadata: TaskMetadata,
+        options: BackfillOptions,
+    ) -> Result<BackfillResult, ExecutorError> {
+        // Reject unknown task types before any date is recorded as a run
+        self.executor_registry.get(&task_type)?;
+        let dates = options.dates()?;
+        let parallelism = options.parallelism();
+
+        log::info!(
+            "Backfilling task {} for {} dates ({} - {}), parallelism {}",
+            metadata.task_id, dates.len(), options.start_date, options.end_date, parallelism
+        );
+
+        let continue_on_error = options.continue_on_error;
+        let stop = AtomicBool::new(false);
+        let stop = &stop;
+        let task_type = &task_type;
+        let metadata = &metadata;
+
+        let runs: Vec<BackfillRun> = stream::iter(dates)
+            .map(|logical_date| async move {
+                if stop.load(Ordering::SeqCst) {
+                    return BackfillRun {
+                        logical_date,
+                        status: BackfillStatus::Skipped,
+                        result: None,
+                        error: None,
+                    };
+                }
+
+                let mut metadata = metadata.clone();
+                metadata.logical_date = Some(logical_date.clone());
+
+                let run = match self.execute_task(task_type.clone(), metadata).await {
+                    Ok(result) => BackfillRun {
+                        logical_date,
+                        status: if result.success { BackfillStatus::Succeeded } else { BackfillStatus::Failed },
+                        result: Some(result),
+                        error: None,
+                    },
+                    Err(e) => BackfillRun {
+                        logical_date,
+                        status: BackfillStatus::Failed,
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                };
+
+                if run.status == BackfillStatus::Failed && !continue_on_error {
+                    stop.store(true, Ordering::SeqCst);
+                }
+                run
+            })
+            .buffered(parallelism)
+            .collect()
+            .await;
+
+        let count = |status| runs.iter().filter(|r| r.status == status).count();
+        let succeeded = count(BackfillStatus::Succeeded);
+        let failed = count(BackfillStatus::Failed);
+        let skipped = count(BackfillStatus::Skipped);
+
+        Ok(BackfillResult {
+            success: failed == 0 && skipped == 0,
+            total: runs.len(),
+            succeeded,
+            failed,
+            skipped,
+            runs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::tests::{engine, metadata, task};
+
+    fn options(start_date: &str, end_date: &str, parallelism: usize, continue_on_error: bool) -> BackfillOptions {
+        BackfillOptions {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            parallelism,
+            continue_on_error,
+        }
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(
+            options("20240227", "2024-03-01", 1, false).dates().unwrap(),
+            vec!["20240227", "20240228", "20240229", "20240301"]
+        );
+        assert_eq!(options("20251026", "20251026", 1, false).dates().unwrap(), vec!["20251026"]);
+        assert!(options("20251026", "20251025", 1, false).dates().is_err());
+
+        // The limit is inclusive of both ends
+        assert_eq!(options("20240101", "20241231", 1, false).dates().unwrap().len(), 366);
+        assert!(options("20240101", "20250101", 1, false).dates().is_err());
+    }
+
+    #[test]
+    fn test_parallelism() {
+        assert_eq!(options("20251026", "20251026", 0, false).parallelism(), 1);
+        assert_eq!(options("20251026", "20251026", 4, false).parallelism(), 4);
+        assert_eq!(options("20251026", "20251026", 10_000, false).parallelism(), MAX_BACKFILL_PARALLELISM);
+    }
+
+    fn statuses(result: &BackfillResult) -> Vec<(&str, BackfillStatus)> {
+        result.runs.iter().map(|run| (run.logical_date.as_str(), run.status)).collect()
+    }
+
+    async fn backfill(config: serde_json::Value, options: BackfillOptions) -> BackfillResult {
+        let engine = engine(vec![task("dwd_sales", serde_json::json!({}))], 4).await;
+        engine.backfill(
+            TaskType::from("test"),
+            metadata(serde_json::json!({ "task_id": "dwd_sales", "config": config })),
+            options,
+        ).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stop_on_error() {
+        let result = backfill(
+            serde_json::json!({ "fail_on": "20251002" }),
+            options("20251001", "20251004", 1, false),
+        ).await;
+
+        assert!(!result.success);
+        assert_eq!((result.succeeded, result.failed, result.skipped), (1, 1, 2));
+        assert_eq!(statuses(&result), vec![
+            ("20251001", BackfillStatus::Succeeded),
+            ("20251002", BackfillStatus::Failed),
+            ("20251003", BackfillStatus::Skipped),
+            ("20251004", BackfillStatus::Skipped),
+        ]);
+        assert!(result.runs[1].result.as_ref().unwrap().message.contains("dwd_sales broke"));
+    }
+
+    #[tokio::test]
+    async fn test_continue_on_error() {
+        let result = backfill(
+            serde_json::json!({ "fail_on": "20251002" }),
+            options("20251001", "20251003", 1, true),
+        ).await;
+
+        assert_eq!(statuses(&result), vec![
+            ("20251001", BackfillStatus::Succeeded),
+            ("20251002", BackfillStatus::Failed),
+            ("20251003", BackfillStatus::Succeeded),
+        ]);
+        assert_eq!(result.runs[2].result.as_ref().unwrap().message, "dwd_sales done for 20251003");
+    }
+
+    #[tokio::test]
+    async fn test_parallel_dates_in_flight_finish() {
+        // With two dates in flight, the one started alongside the failure still runs
+        let result = backfill(
+            serde_json::json!({ "fail_on": "20251001", "delay_ms": 50 }),
+            options("20251001", "20251004", 2, false),
+        ).await;
+
+        assert_eq!(statuses(&result), vec![
+            ("20251001", BackfillStatus::Failed),
+            ("20251002", BackfillStatus::Succeeded),
+            ("20251003", BackfillStatus::Skipped),
+            ("20251004", BackfillStatus::Skipped),
+        ]);
+    }
+}
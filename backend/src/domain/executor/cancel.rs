@@ -0,0 +1,211 @@
+// Run cancellation
+//
+// Every run gets a CancelHandle. Cancelling it aborts the tokio task executing the
+// run and cancels the statements still running on PostgreSQL, in the transactions
+// and dedicated connections opened through `begin_for_run` and `connect_dedicated`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DatabaseTransaction,
+    DbErr, Statement, TransactionTrait,
+};
+use tokio::sync::Notify;
+use tokio::task::AbortHandle;
+use crate::domain::connection_pool::{self, ConnectionPools};
+use crate::domain::error::ExecutorError;
+use crate::entities::data_source;
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    state: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    // Wakes up `cancelled()` waiters
+    notify: Notify,
+    abort: Mutex<Option<AbortHandle>>,
+    // PostgreSQL backends running statements of the run
+    pg_backends: Mutex<Vec<PgBackend>>,
+}
+
+// A PostgreSQL backend used by a run, cancelled through another connection of its pool
+#[derive(Debug, Clone)]
+struct PgBackend {
+    pool: Arc<DatabaseConnection>,
+    pid: i32,
+    // Start of the registered transaction; a pooled connection serving another
+    // transaction since is left alone
+    xact_start: Option<String>,
+}
+
+impl CancelHandle {
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the run is cancelled
+    pub async fn cancelled(&self) {
+        let notified = self.state.notify.notified();
+        tokio::pin!(notified);
+        // Register before checking the flag, so a concurrent cancel is not missed
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+
+    /// Set the task executing the run, aborting it right away if already cancelled
+    pub fn set_abort_handle(&self, abort: AbortHandle) {
+        if self.is_cancelled() {
+            abort.abort();
+        }
+        if let Ok(mut slot) = self.state.abort.lock() {
+            *slot = Some(abort);
+        }
+    }
+
+    fn register_postgres_backend(&self, backend: PgBackend) {
+        if let Ok(mut backends) = self.state.pg_backends.lock() {
+            backends.push(backend);
+        }
+    }
+
+    /// Forget the task and connections of a finished attempt
+    pub fn finish_attempt(&self) {
+        if let Ok(mut slot) = self.state.abort.lock() {
+            slot.take();
+        }
+        if let Ok(mut backends) = self.state.pg_backends.lock() {
+            backends.clear();
+        }
+    }
+
+    /// Abort the run and cancel its statements still running on PostgreSQL
+    pub async fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+        self.interrupt().await;
+    }
+
+    /// Abort the current attempt without cancelling the run, e.g. on timeout
+    pub async fn interrupt(&self) {
+        if let Some(abort) = self.state.abort.lock().ok().and_then(|mut slot| slot.take()) {
+            abort.abort();
+        }
+
+        let backends = self.state.pg_backends.lock()
+            .map(|mut backends| std::mem::take(&mut *backends))
+            .unwrap_or_default();
+
+        for backend in backends {
+            match cancel_postgres_backend(&backend).await {
+                Ok(()) => log::info!("Cancelled statement on PostgreSQL backend {}", backend.pid),
+                Err(e) => log::warn!("Failed to cancel PostgreSQL backend {}: {}", backend.pid, e),
+            }
+        }
+    }
+}
+
+async fn cancel_postgres_backend(backend: &PgBackend) -> Result<(), DbErr> {
+    let statement = match &backend.xact_start {
+        Some(xact_start) => Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT pg_cancel_backend(pid) FROM pg_stat_activity WHERE pid = $1 AND xact_start = $2::timestamptz",
+            [backend.pid.into(), xact_start.clone().into()],
+        ),
+        None => Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT pg_cancel_backend($1)",
+            [backend.pid.into()],
+        ),
+    };
+    backend.pool.execute(statement).await.map(|_| ())
+}
+
+/// Connect to a target database for a run, through the shared pool of the data source.
+///
+/// Statements outside of `begin_for_run` transactions are only stopped by aborting the
+/// run, PostgreSQL finishes them in the background.
+pub async fn connect_for_run(
+    pools: &ConnectionPools,
+    data_source: &data_source::Model,
+) -> Result<Arc<DatabaseConnection>, ExecutorError> {
+    Ok(pools.get(data_source).await?)
+}
+
+/// Begin a transaction for a run.
+///
+/// On PostgreSQL the backend PID of the transaction is registered, so that a running
+/// statement is cancelled together with the run.
+pub async fn begin_for_run(
+    db: &Arc<DatabaseConnection>,
+    cancel: &CancelHandle,
+) -> Result<DatabaseTransaction, ExecutorError> {
+    let txn = db.begin()
+        .await
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to begin transaction: {}", e)))?;
+
+    if db.get_database_backend() == DatabaseBackend::Postgres {
+        // now() is the start time of the current transaction
+        let row = txn.query_one(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT pg_backend_pid() AS pid, now()::text AS xact_start",
+        ))
+            .await
+            .ok()
+            .flatten();
+        let backend = row.and_then(|row| Some(PgBackend {
+            pool: db.clone(),
+            pid: row.try_get::<i32>("", "pid").ok()?,
+            xact_start: Some(row.try_get::<String>("", "xact_start").ok()?),
+        }));
+
+        match backend {
+            Some(backend) => cancel.register_postgres_backend(backend),
+            None => log::warn!("Failed to read PostgreSQL backend PID, statements cannot be cancelled"),
+        }
+    }
+
+    Ok(txn)
+}
+
+/// Open a single connection owned by the run, for statements sharing session state
+/// outside of a transaction (SET, temporary tables). The caller closes it.
+pub async fn connect_dedicated(
+    pools: &ConnectionPools,
+    data_source: &data_source::Model,
+    cancel: &CancelHandle,
+) -> Result<DatabaseConnection, ExecutorError> {
+    let (backend, connection_string) = connection_pool::connection_target(data_source)?;
+
+    let mut options = ConnectOptions::new(connection_string);
+    options.max_connections(1);
+
+    let db = Database::connect(options)
+        .await
+        .map_err(|_| ExecutorError::ConnectionFailed)?;
+
+    if backend == DatabaseBackend::Postgres {
+        let pid = db.query_one(Statement::from_string(backend, "SELECT pg_backend_pid() AS pid"))
+            .await
+            .ok()
+            .flatten()
+            .and_then(|row| row.try_get::<i32>("", "pid").ok());
+
+        match pid {
+            // The statement is cancelled through the shared pool, the connection being busy
+            Some(pid) => cancel.register_postgres_backend(PgBackend {
+                pool: pools.get(data_source).await?,
+                pid,
+                xact_start: None,
+            }),
+            None => log::warn!("Failed to read PostgreSQL backend PID, statements cannot be cancelled"),
+        }
+    }
+
+    Ok(db)
+}
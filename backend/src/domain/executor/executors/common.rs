@@ -0,0 +1,219 @@
+// Helpers shared by the built-in executors
+use std::path::{Path, PathBuf};
+use sea_orm::{DatabaseBackend, DatabaseConnection, FromQueryResult, JsonValue, QueryResult};
+use crate::domain::error::ExecutorError;
+use crate::entities::{data_source, storage};
+
+/// Find a data source of the execution context by id
+pub fn find_data_source<'a>(
+    data_sources: &'a [data_source::Model],
+    id: &str,
+) -> Result<&'a data_source::Model, ExecutorError> {
+    data_sources
+        .iter()
+        .find(|ds| ds.id == id)
+        .ok_or_else(|| ExecutorError::ExecutionFailed(format!("Data source {} not found", id)))
+}
+
+/// Find a storage of the execution context by id
+pub fn find_storage<'a>(
+    storages: &'a [storage::Model],
+    id: &str,
+) -> Result<&'a storage::Model, ExecutorError> {
+    storages
+        .iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| ExecutorError::ExecutionFailed(format!("Storage {} not found", id)))
+}
+
+/// Download a file from storage chunk by chunk into a local file, returning its size
+pub async fn download_to_file(
+    file_path: &str,
+    storage: &storage::Model,
+    destination: &Path,
+) -> Result<u64, ExecutorError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut response = send_download(file_path, storage).await?;
+
+    let mut file = tokio::fs::File::create(destination)
+        .await
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to create temporary file: {}", e)))?;
+
+    let mut size = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| ExecutorError::DownloadFailed(format!("Failed to read file bytes: {}", e)))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to write temporary file: {}", e)))?;
+        size += chunk.len() as u64;
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to write temporary file: {}", e)))?;
+
+    Ok(size)
+}
+
+/// Request a file from storage, checking the response status
+async fn send_download(
+    file_path: &str,
+    storage: &storage::Model,
+) -> Result<reqwest::Response, ExecutorError> {
+    // Build download URL
+    let download_url = format!("{}/{}", storage.download_endpoint, file_path);
+
+    // Create HTTP client
+    let client = reqwest::Client::new();
+
+    // Get authentication headers if needed
+    let mut request = client.get(&download_url);
+
+    // Add authentication if configured
+    if let Some(access_key) = storage.auth_config.get("access_key").and_then(|v| v.as_str()) {
+        if let Some(_secret_key) = storage.auth_config.get("secret_key").and_then(|v| v.as_str()) {
+            // Simple bearer token auth (adjust based on actual storage auth mechanism)
+            request = request.header("Authorization", format!("Bearer {}", access_key));
+        }
+    }
+
+    // Download file; network errors and server errors are transient
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ExecutorError::DownloadFailed(format!("Failed to download file: {}", e)))?;
+
+    let status = response.status();
+    if status.is_server_error() {
+        return Err(ExecutorError::DownloadFailed(format!("Download failed with status: {}", status)));
+    }
+    if !status.is_success() {
+        return Err(ExecutorError::ExecutionFailed(
+            format!("Download failed with status: {}", status)
+        ));
+    }
+
+    Ok(response)
+}
+
+// A file in the temporary directory, removed when dropped
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Reserve a unique path, keeping the extension of `file_name`
+    pub fn new(file_name: &str) -> Self {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| format!(".{}", e))
+            .unwrap_or_default();
+        Self {
+            path: std::env::temp_dir().join(format!("data-lakehouse-{}{}", uuid::Uuid::new_v4(), extension)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // The file does not exist when the download failed early
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Upload a file to storage, the counterpart of `download_to_file`
+pub async fn upload_file(
+    file_path: &str,
+    storage: &storage::Model,
+    content: Vec<u8>,
+    content_type: &str,
+) -> Result<(), ExecutorError> {
+    let upload_url = format!("{}/{}", storage.upload_endpoint, file_path);
+
+    let mut request = reqwest::Client::new()
+        .put(&upload_url)
+        .header("Content-Type", content_type)
+        .body(content);
+
+    if let Some(access_key) = storage.auth_config.get("access_key").and_then(|v| v.as_str()) {
+        if storage.auth_config.get("secret_key").and_then(|v| v.as_str()).is_some() {
+            request = request.header("Authorization", format!("Bearer {}", access_key));
+        }
+    }
+
+    // Network errors and server errors are transient
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ExecutorError::UploadFailed(format!("Failed to upload file: {}", e)))?;
+
+    let status = response.status();
+    if status.is_server_error() {
+        return Err(ExecutorError::UploadFailed(format!("Upload failed with status: {}", status)));
+    }
+    if !status.is_success() {
+        return Err(ExecutorError::ExecutionFailed(
+            format!("Upload failed with status: {}", status)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Names and type names of the columns produced by a query, without running it
+pub async fn describe_columns(
+    db: &DatabaseConnection,
+    backend: DatabaseBackend,
+    query: &str,
+) -> Result<Vec<(String, String)>, ExecutorError> {
+    use sqlx::{Column, Executor as _, TypeInfo};
+
+    fn columns<C: Column>(columns: &[C]) -> Vec<(String, String)> {
+        columns
+            .iter()
+            .map(|c| (c.name().to_string(), c.type_info().name().to_string()))
+            .collect()
+    }
+
+    let described = match backend {
+        DatabaseBackend::Postgres => db.get_postgres_connection_pool()
+            .describe(query)
+            .await
+            .map(|d| columns(d.columns())),
+        DatabaseBackend::MySql => db.get_mysql_connection_pool()
+            .describe(query)
+            .await
+            .map(|d| columns(d.columns())),
+        _ => return Err(ExecutorError::ExecutionFailed("Unsupported database type".to_string())),
+    };
+
+    described.map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid query: {}", e)))
+}
+
+/// Values of a query row as JSON, in column order
+pub fn row_json(row: &QueryResult, columns: &[String]) -> Result<Vec<JsonValue>, ExecutorError> {
+    let mut values = JsonValue::from_query_result(row, "")
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to read row: {}", e)))?;
+
+    columns
+        .iter()
+        .map(|column| match values.get_mut(column) {
+            Some(value) => Ok(value.take()),
+            // Types without a JSON mapping are read as text
+            None => row.try_get::<Option<String>>("", column)
+                .map(|value| value.map_or(JsonValue::Null, JsonValue::String))
+                .map_err(|_| ExecutorError::ExecutionFailed(
+                    format!("Unsupported type of column '{}', cast it in the query", column)
+                )),
+        })
+        .collect()
+}
@@ -0,0 +1,198 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, QueryResult, Statement, StreamTrait,
+};
+use serde::Deserialize;
+use crate::domain::error::ExecutorError;
+use super::common;
+use super::table_loader::{column_values, quote_identifier, value_text, ColumnKind, LoadMode, TableLoader};
+use super::super::{cancel, Executor, ExecutorContext, ExecutionResult, TaskMetadata};
+
+// Copy Executor - copies the result of a query on one data source into a table of another
+pub struct CopyExecutor;
+
+// Options of a copy task
+#[derive(Debug, Clone, Deserialize)]
+struct CopyConfig {
+    source_data_source_id: String,
+    query: String,
+    target_data_source_id: String,
+    table: String,
+    // Partition of the target table, e.g. {"dt": "${dt}"}; columns missing
+    // from the query result are added to every row
+    #[serde(default)]
+    partition: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    mode: LoadMode,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    // Create the target table from the query columns when it does not exist
+    #[serde(default)]
+    create_table: bool,
+}
+
+fn default_batch_size() -> usize {
+    1000
+}
+
+#[async_trait]
+impl Executor for CopyExecutor {
+    fn description(&self) -> &str {
+        "Copy the result of a query on one data source into a table of another, in batches"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["source_data_source_id", "query", "target_data_source_id", "table"],
+            "properties": {
+                "source_data_source_id": { "type": "string", "description": "Data source the query runs on" },
+                "query": { "type": "string", "description": "SELECT statement producing the rows to copy" },
+                "target_data_source_id": { "type": "string", "description": "Data source of the target table" },
+                "table": { "type": "string", "description": "Target table, optionally schema-qualified" },
+                "partition": { "type": "object", "description": "Partition of the target table, e.g. {\"dt\": \"${dt}\"}" },
+                "mode": { "type": "string", "enum": ["append", "overwrite", "overwrite_partition"], "default": "append" },
+                "batch_size": { "type": "integer", "default": 1000, "description": "Rows per INSERT statement" },
+                "create_table": { "type": "boolean", "default": false, "description": "Create the target table from the query columns if missing" }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        metadata: TaskMetadata,
+        context: ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Render template variables before reading the config
+        let config: CopyConfig = serde_json::from_value(metadata.render_config()?)
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid copy config: {}", e)))?;
+
+        let source = common::find_data_source(&context.data_sources, &config.source_data_source_id)?;
+        let target = common::find_data_source(&context.data_sources, &config.target_data_source_id)?;
+        let source_db = cancel::connect_for_run(&context.pools, source).await?;
+        let target_db = cancel::connect_for_run(&context.pools, target).await?;
+        let source_backend = source_db.get_database_backend();
+        let target_backend = target_db.get_database_backend();
+
+        let columns: Vec<(String, ColumnKind)> = common::describe_columns(&source_db, source_backend, &config.query)
+            .await?
+            .into_iter()
+            .map(|(name, type_name)| (name, ColumnKind::from_type_name(&type_name)))
+            .collect();
+        let column_names: Vec<String> = columns.iter().map(|(name, _)| name.clone()).collect();
+
+        let partition = column_values(&config.partition);
+        let constants: Vec<(String, String)> = partition
+            .iter()
+            .filter(|(column, _)| !column_names.contains(column))
+            .cloned()
+            .collect();
+
+        let txn = cancel::begin_for_run(&target_db, &context.cancel).await?;
+        context.changes.record(target, &config.table);
+
+        if config.create_table {
+            let mut definitions: Vec<String> = columns
+                .iter()
+                .map(|(name, kind)| format!(
+                    "{} {}",
+                    quote_identifier(target_backend, name),
+                    kind.sql_type(target_backend)
+                ))
+                .collect();
+            definitions.extend(constants.iter().map(|(name, _)| format!(
+                "{} {}",
+                quote_identifier(target_backend, name),
+                ColumnKind::Text.sql_type(target_backend)
+            )));
+
+            let create_sql = format!(
+                "CREATE TABLE IF NOT EXISTS {} ({})",
+                quote_identifier(target_backend, &config.table),
+                definitions.join(", ")
+            );
+            txn.execute(Statement::from_string(target_backend, create_sql))
+                .await
+                .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to create table: {}", e)))?;
+        }
+
+        let loader = TableLoader::new(target_backend, &config.table, column_names.clone(), constants)
+            .with_partition(partition);
+
+        // The target is written in one transaction, so a failed copy leaves it untouched
+        let rows_deleted = loader.prepare(&txn, config.mode).await?;
+
+        let batch_size = config.batch_size.max(1);
+        let mut rows_copied = 0;
+        let mut batch = Vec::with_capacity(batch_size);
+
+        // The source is read inside a transaction as well, so that a cancelled run stops the query
+        let source_txn = cancel::begin_for_run(&source_db, &context.cancel).await?;
+        let mut stream = source_txn.stream(Statement::from_string(source_backend, config.query.clone()))
+            .await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to run query: {}", e)))?;
+
+        while let Some(row) = stream.next().await {
+            let row = row
+                .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to read row: {}", e)))?;
+            batch.push(row_values(&row, &column_names, target_backend)?);
+
+            if batch.len() >= batch_size {
+                rows_copied += loader.insert_batch(&txn, &batch).await?;
+                batch.clear();
+                context.progress.report(serde_json::json!({ "rows_copied": rows_copied }));
+            }
+        }
+        rows_copied += loader.insert_batch(&txn, &batch).await?;
+        drop(stream);
+
+        txn.commit().await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to commit transaction: {}", e)))?;
+        source_txn.commit().await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(ExecutionResult {
+            success: true,
+            message: format!("Copied {} rows into {}", rows_copied, config.table),
+            data: Some(serde_json::json!({
+                "table": config.table,
+                "columns": column_names,
+                "rows_copied": rows_copied,
+                "rows_deleted": rows_deleted,
+            })),
+            chain: Vec::new(),
+        })
+    }
+}
+
+/// Values of a source row, rendered for the target database
+fn row_values(
+    row: &QueryResult,
+    columns: &[String],
+    target: DatabaseBackend,
+) -> Result<Vec<Option<String>>, ExecutorError> {
+    Ok(common::row_json(row, columns)?
+        .iter()
+        .map(|value| value_text(value, target))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_type_mapping() {
+        // MySQL to PostgreSQL
+        let kind = ColumnKind::from_type_name("DATETIME");
+        assert_eq!(kind.sql_type(DatabaseBackend::Postgres), "TIMESTAMP");
+        assert_eq!(ColumnKind::from_type_name("BIGINT UNSIGNED").sql_type(DatabaseBackend::Postgres), "NUMERIC");
+        assert_eq!(ColumnKind::from_type_name("VARCHAR").sql_type(DatabaseBackend::Postgres), "TEXT");
+
+        // PostgreSQL to MySQL
+        assert_eq!(ColumnKind::from_type_name("TIMESTAMPTZ").sql_type(DatabaseBackend::MySql), "DATETIME(6)");
+        assert_eq!(ColumnKind::from_type_name("JSONB").sql_type(DatabaseBackend::MySql), "JSON");
+        assert_eq!(ColumnKind::from_type_name("INT4").sql_type(DatabaseBackend::MySql), "INT");
+    }
+}
@@ -0,0 +1,431 @@
+use std::io::{BufRead, BufReader, Read};
+use async_trait::async_trait;
+use sea_orm::ConnectionTrait;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use crate::domain::error::ExecutorError;
+use super::common;
+use super::table_loader::{column_values, LoadMode, TableLoader};
+use super::super::{cancel, Executor, ExecutorContext, ExecutionResult, TaskMetadata};
+
+// CSV Executor - loads a CSV file from storage into an existing table
+pub struct CsvExecutor;
+
+// Options of a CSV import task
+#[derive(Debug, Clone, Deserialize)]
+struct CsvConfig {
+    file_path: String,
+    storage_id: String,
+    data_source_id: String,
+    table: String,
+    #[serde(flatten)]
+    format: CsvFormat,
+    // Target columns in file order; the header row is used when omitted
+    #[serde(default)]
+    columns: Option<Vec<String>>,
+    // Constant values appended to every row, e.g. {"dt": "${dt}"}
+    #[serde(default)]
+    extra_columns: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    mode: LoadMode,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+}
+
+// How the file is decoded and split into records
+#[derive(Debug, Clone, Deserialize)]
+struct CsvFormat {
+    #[serde(default = "default_delimiter")]
+    delimiter: String,
+    #[serde(default = "default_quote")]
+    quote: String,
+    #[serde(default = "default_encoding")]
+    encoding: String,
+    #[serde(default = "default_header")]
+    header: bool,
+    // Lines skipped before the header (or the first record), whatever their content
+    #[serde(default)]
+    skip_rows: usize,
+    // Values loaded as NULL
+    #[serde(default = "default_null_values")]
+    null_values: Vec<String>,
+}
+
+fn default_delimiter() -> String {
+    ",".to_string()
+}
+
+fn default_quote() -> String {
+    "\"".to_string()
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+fn default_header() -> bool {
+    true
+}
+
+fn default_null_values() -> Vec<String> {
+    vec![String::new()]
+}
+
+fn default_batch_size() -> usize {
+    500
+}
+
+// Read from the file by the reader thread: the header (if any) first, then batches of records
+#[derive(Debug, PartialEq)]
+enum CsvEvent {
+    Header(Option<Vec<String>>),
+    Records(Vec<Vec<Option<String>>>),
+}
+
+#[async_trait]
+impl Executor for CsvExecutor {
+    fn description(&self) -> &str {
+        "Load a CSV file from storage into a table of a data source"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["file_path", "storage_id", "data_source_id", "table"],
+            "properties": {
+                "file_path": { "type": "string", "description": "Path of the file in the storage" },
+                "storage_id": { "type": "string", "description": "Storage to download the file from" },
+                "data_source_id": { "type": "string", "description": "Target data source" },
+                "table": { "type": "string", "description": "Target table, optionally schema-qualified" },
+                "delimiter": { "type": "string", "default": ",", "description": "Field delimiter, a single character or \"\\t\"" },
+                "quote": { "type": "string", "default": "\"", "description": "Quote character" },
+                "encoding": { "type": "string", "default": "utf-8", "description": "File encoding, e.g. utf-8, gbk, gb18030; a BOM is detected and removed" },
+                "header": { "type": "boolean", "default": true, "description": "Whether the first row holds column names" },
+                "skip_rows": { "type": "integer", "default": 0, "description": "Lines skipped before the header" },
+                "columns": { "type": "array", "items": { "type": "string" }, "description": "Target columns in file order, instead of the header" },
+                "extra_columns": { "type": "object", "description": "Constant values added to every row, e.g. {\"dt\": \"${dt}\"}" },
+                "mode": { "type": "string", "enum": ["append", "truncate", "replace_partition"], "default": "append" },
+                "batch_size": { "type": "integer", "default": 500, "description": "Rows per INSERT statement" },
+                "null_values": { "type": "array", "items": { "type": "string" }, "default": [""], "description": "Values loaded as NULL" }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        metadata: TaskMetadata,
+        context: ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Render template variables before reading the config
+        let config: CsvConfig = serde_json::from_value(metadata.render_config()?)
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid CSV config: {}", e)))?;
+
+        let data_source = common::find_data_source(&context.data_sources, &config.data_source_id)?;
+        let storage = common::find_storage(&context.storages, &config.storage_id)?;
+
+        // Download the file into a temporary file, removed when the run ends
+        let file = common::TempFile::new(&config.file_path);
+        common::download_to_file(&config.file_path, storage, file.path()).await?;
+
+        // The file is read on a blocking thread while the records are imported; the
+        // bounded channel keeps only a couple of batches in memory
+        let (events, mut receiver) = mpsc::channel(2);
+        let reader = {
+            let format = config.format.clone();
+            let path = file.path().to_path_buf();
+            let batch_size = config.batch_size.max(1);
+            tokio::task::spawn_blocking(move || {
+                let file = std::fs::File::open(&path)
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to open CSV file: {}", e)))?;
+                read_csv(file, &format, batch_size, &events)
+            })
+        };
+
+        let header = match receiver.recv().await {
+            Some(CsvEvent::Header(header)) => header,
+            // The reader stops before the header only on an error
+            _ => {
+                join_reader(reader).await?;
+                return Err(ExecutorError::ExecutionFailed("CSV file could not be read".to_string()));
+            }
+        };
+        let columns = match (&config.columns, header) {
+            (Some(columns), _) => columns.clone(),
+            (None, Some(header)) => header,
+            (None, None) => return Err(ExecutorError::ExecutionFailed(
+                "'columns' is required when the file has no header".to_string()
+            )),
+        };
+        let constants = column_values(&config.extra_columns);
+
+        let db = cancel::connect_for_run(&context.pools, data_source).await?;
+        let loader = TableLoader::new(db.get_database_backend(), &config.table, columns, constants);
+        let txn = cancel::begin_for_run(&db, &context.cancel).await?;
+
+        // The whole file is loaded in one transaction, so a failed run leaves the table untouched
+        context.changes.record(data_source, &config.table);
+        let rows_deleted = loader.prepare(&txn, config.mode).await?;
+
+        let mut rows_imported = 0;
+        while let Some(event) = receiver.recv().await {
+            if let CsvEvent::Records(batch) = event {
+                rows_imported += loader.insert_batch(&txn, &batch).await?;
+
+                context.progress.report(serde_json::json!({
+                    "rows_imported": rows_imported,
+                }));
+            }
+        }
+
+        // The channel also closes when reading fails, which must not commit a partial import
+        join_reader(reader).await?;
+
+        txn.commit().await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(ExecutionResult {
+            success: true,
+            message: format!("CSV file imported into {}. Total rows: {}", config.table, rows_imported),
+            data: Some(serde_json::json!({
+                "table": config.table,
+                "rows_imported": rows_imported,
+                "rows_deleted": rows_deleted,
+            })),
+            chain: Vec::new(),
+        })
+    }
+}
+
+async fn join_reader(reader: JoinHandle<Result<(), ExecutorError>>) -> Result<(), ExecutorError> {
+    reader.await
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("CSV reader failed: {}", e)))?
+}
+
+/// Read a CSV file, sending its header first and then its records in batches.
+///
+/// `skip_rows` lines are skipped before the records are parsed. Blank lines are
+/// ignored; values listed in `null_values` become `None`.
+fn read_csv(
+    source: impl Read,
+    format: &CsvFormat,
+    batch_size: usize,
+    events: &mpsc::Sender<CsvEvent>,
+) -> Result<(), ExecutorError> {
+    fn read_error(e: impl std::fmt::Display) -> ExecutorError {
+        ExecutorError::ExecutionFailed(format!("Failed to read CSV record: {}", e))
+    }
+    let send = |event| events.blocking_send(event)
+        .map_err(|_| ExecutorError::ExecutionFailed("CSV import stopped".to_string()));
+
+    let delimiter = single_byte("delimiter", &format.delimiter)?;
+    let quote = single_byte("quote", &format.quote)?;
+    let mut text = BufReader::new(DecodingReader::new(source, encoding(&format.encoding)?));
+
+    let mut line = Vec::new();
+    for _ in 0..format.skip_rows {
+        line.clear();
+        if text.read_until(b'\n', &mut line).map_err(read_error)? == 0 {
+            break;
+        }
+    }
+
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quote(quote)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text);
+
+    let mut header_sent = false;
+    if !format.header {
+        send(CsvEvent::Header(None))?;
+        header_sent = true;
+    }
+
+    let mut batch = Vec::with_capacity(batch_size);
+    for record in reader.records() {
+        let record = record.map_err(read_error)?;
+        if !header_sent {
+            send(CsvEvent::Header(Some(record.iter().map(|v| v.trim().to_string()).collect())))?;
+            header_sent = true;
+            continue;
+        }
+        if record.iter().all(|v| v.is_empty()) {
+            continue;
+        }
+
+        batch.push(
+            record.iter()
+                .map(|v| (!format.null_values.iter().any(|n| n == v)).then(|| v.to_string()))
+                .collect()
+        );
+        if batch.len() >= batch_size {
+            send(CsvEvent::Records(std::mem::replace(&mut batch, Vec::with_capacity(batch_size))))?;
+        }
+    }
+
+    if !header_sent {
+        return Err(ExecutorError::ExecutionFailed("CSV file has no header row".to_string()));
+    }
+    if !batch.is_empty() {
+        send(CsvEvent::Records(batch))?;
+    }
+    Ok(())
+}
+
+/// Encoding of a label, e.g. utf-8 or gbk
+fn encoding(label: &str) -> Result<&'static encoding_rs::Encoding, ExecutorError> {
+    let label = label.trim().to_lowercase();
+    // Python-style name of UTF-8 with BOM
+    let label = label.strip_suffix("-sig").unwrap_or(&label);
+
+    encoding_rs::Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| ExecutorError::ExecutionFailed(format!("Unsupported encoding: {}", label)))
+}
+
+// Decodes a byte stream into UTF-8 chunk by chunk, a BOM taking precedence over the encoding
+struct DecodingReader<R> {
+    inner: R,
+    decoder: encoding_rs::Decoder,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    // Start of the decoded bytes not yet read
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    fn new(inner: R, encoding: &'static encoding_rs::Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            input: vec![0; 8192],
+            output: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Decode the next chunk of input into `output`
+    fn fill(&mut self) -> std::io::Result<()> {
+        let read = self.inner.read(&mut self.input)?;
+        let last = read == 0;
+
+        // Sized for the worst case, so the whole chunk is decoded at once
+        let capacity = self.decoder.max_utf8_buffer_length(read)
+            .ok_or_else(|| std::io::Error::other("CSV chunk too large to decode"))?;
+        self.output.resize(capacity, 0);
+        let (_, _, written, had_errors) = self.decoder.decode_to_utf8(&self.input[..read], &mut self.output, last);
+        if had_errors {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("File is not valid {}", self.decoder.encoding().name()),
+            ));
+        }
+
+        self.output.truncate(written);
+        self.position = 0;
+        self.finished = last;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // A chunk may end inside a character and decode to nothing
+        while self.position == self.output.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let count = buf.len().min(self.output.len() - self.position);
+        buf[..count].copy_from_slice(&self.output[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+fn single_byte(name: &str, value: &str) -> Result<u8, ExecutorError> {
+    match value {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+        _ => Err(ExecutorError::ExecutionFailed(
+            format!("'{}' must be a single ASCII character, got '{}'", name, value)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(options: serde_json::Value) -> CsvFormat {
+        serde_json::from_value(options).unwrap()
+    }
+
+    // Events sent while reading the given bytes
+    fn read(bytes: &[u8], options: serde_json::Value, batch_size: usize) -> Result<Vec<CsvEvent>, ExecutorError> {
+        let (events, mut receiver) = mpsc::channel(100);
+        read_csv(bytes, &format(options), batch_size, &events)?;
+        drop(events);
+
+        let mut received = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            received.push(event);
+        }
+        Ok(received)
+    }
+
+    fn record(values: &[Option<&str>]) -> Vec<Option<String>> {
+        values.iter().map(|v| v.map(str::to_string)).collect()
+    }
+
+    #[test]
+    fn test_read_csv() {
+        // UTF-8 with BOM, a quoted delimiter and a blank line
+        let bytes = "\u{feff}id,name\n1,\"Smith, J\"\n\n2,\n".as_bytes();
+        assert_eq!(read(bytes, serde_json::json!({}), 500).unwrap(), vec![
+            CsvEvent::Header(Some(vec!["id".to_string(), "name".to_string()])),
+            CsvEvent::Records(vec![record(&[Some("1"), Some("Smith, J")]), record(&[Some("2"), None])]),
+        ]);
+
+        // GBK, tab separated, with a title line holding a quote and no header
+        let (gbk, _, _) = encoding_rs::GBK.encode("\"订单报表\n1\t北京\n2\t上海\n");
+        assert_eq!(read(&gbk, serde_json::json!({
+            "encoding": "gbk",
+            "delimiter": "\\t",
+            "header": false,
+            "skip_rows": 1,
+        }), 1).unwrap(), vec![
+            CsvEvent::Header(None),
+            CsvEvent::Records(vec![record(&[Some("1"), Some("北京")])]),
+            CsvEvent::Records(vec![record(&[Some("2"), Some("上海")])]),
+        ]);
+
+        assert!(read(b"a;b", serde_json::json!({ "delimiter": ";;" }), 500).is_err());
+        assert!(read(b"", serde_json::json!({}), 500).is_err());
+        assert!(read(&[0x81, 0x30], serde_json::json!({}), 500).is_err());
+    }
+
+    #[test]
+    fn test_decode_in_chunks() {
+        // Characters split across chunks of the underlying reader are decoded whole
+        struct Chunks<'a>(&'a [u8]);
+        impl Read for Chunks<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let count = self.0.len().min(buf.len()).min(3);
+                buf[..count].copy_from_slice(&self.0[..count]);
+                self.0 = &self.0[count..];
+                Ok(count)
+            }
+        }
+
+        let (gbk, _, _) = encoding_rs::GBK.encode("北京,上海\n");
+        let mut text = String::new();
+        DecodingReader::new(Chunks(&gbk), encoding("gbk").unwrap()).read_to_string(&mut text).unwrap();
+        assert_eq!(text, "北京,上海\n");
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use async_trait::async_trait;
+use sea_orm::ConnectionTrait;
+use serde::Deserialize;
+use serde_json::Value;
+use crate::domain::error::ExecutorError;
+use super::common;
+use super::table_loader::{column_values, value_text, LoadMode, TableLoader};
+use super::super::{cancel, Executor, ExecutorContext, ExecutionResult, TaskMetadata};
+
+// HTTP Executor - loads records returned by a REST API into a registered data table
+pub struct HttpExecutor;
+
+// Options of an HTTP ingestion task
+#[derive(Debug, Clone, Deserialize)]
+struct HttpConfig {
+    url: String,
+    #[serde(default = "default_method")]
+    method: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    // Query string parameters sent with every request
+    #[serde(default)]
+    params: HashMap<String, String>,
+    // JSON body of POST requests
+    #[serde(default)]
+    body: Option<Value>,
+    // JSON pointer to the array of records in a response, e.g. "/data/items"
+    #[serde(default)]
+    records_pointer: String,
+    #[serde(default)]
+    pagination: Pagination,
+    #[serde(default = "default_max_pages")]
+    max_pages: u64,
+    #[serde(default = "default_request_timeout_seconds")]
+    request_timeout_seconds: u64,
+    // Target table, registered under data tables
+    data_table_id: String,
+    // JSON pointers into a record by column name; a column defaults to the field of the same name
+    #[serde(default)]
+    fields: HashMap<String, String>,
+    // Values of the partition columns, e.g. {"dt": "${dt}"}
+    #[serde(default)]
+    partition: serde_json::Map<String, Value>,
+    #[serde(default)]
+    mode: LoadMode,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_max_pages() -> u64 {
+    1000
+}
+
+fn default_request_timeout_seconds() -> u64 {
+    60
+}
+
+// How further pages are requested
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Pagination {
+    // A single request
+    #[default]
+    None,
+    // Numbered pages; stops on an empty or short page
+    Page {
+        #[serde(default = "default_page_param")]
+        param: String,
+        #[serde(default = "default_page_start")]
+        start: u64,
+        #[serde(default)]
+        size_param: Option<String>,
+        #[serde(default)]
+        size: Option<u64>,
+    },
+    // Cursor read from each response; stops when it is missing or empty
+    Cursor {
+        #[serde(default = "default_cursor_param")]
+        param: String,
+        // JSON pointer to the next cursor in a response, e.g. "/meta/next_cursor"
+        pointer: String,
+    },
+}
+
+fn default_page_param() -> String {
+    "page".to_string()
+}
+
+fn default_page_start() -> u64 {
+    1
+}
+
+fn default_cursor_param() -> String {
+    "cursor".to_string()
+}
+
+impl Pagination {
+    /// Parameters of the first request
+    fn first(&self) -> Vec<(String, String)> {
+        match self {
+            Pagination::None | Pagination::Cursor { .. } => Vec::new(),
+            Pagination::Page { start, .. } => self.page_params(*start),
+        }
+    }
+
+    /// Parameters of the request following a response, `None` on the last page
+    fn next(&self, page_index: u64, response: &Value, record_count: usize) -> Option<Vec<(String, String)>> {
+        if record_count == 0 {
+            return None;
+        }
+        match self {
+            Pagination::None => None,
+            Pagination::Page { start, size, .. } => {
+                if size.is_some_and(|size| (record_count as u64) < size) {
+                    return None;
+                }
+                Some(self.page_params(start + page_index + 1))
+            }
+            Pagination::Cursor { param, pointer } => {
+                let cursor = match response.pointer(pointer) {
+                    Some(Value::String(s)) if !s.is_empty() => s.clone(),
+                    Some(Value::Number(n)) => n.to_string(),
+                    _ => return None,
+                };
+                Some(vec![(param.clone(), cursor)])
+            }
+        }
+    }
+
+    fn page_params(&self, page: u64) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if let Pagination::Page { param, size_param, size, .. } = self {
+            params.push((param.clone(), page.to_string()));
+            if let (Some(size_param), Some(size)) = (size_param, size) {
+                params.push((size_param.clone(), size.to_string()));
+            }
+        }
+        params
+    }
+}
+
+/// Records of a response, found by a JSON pointer ("" being the whole response)
+fn extract_records(response: &Value, pointer: &str) -> Result<Vec<Value>, ExecutorError> {
+    match response.pointer(pointer) {
+        Some(Value::Array(records)) => Ok(records.clone()),
+        Some(Value::Null) => Ok(Vec::new()),
+        Some(_) => Err(ExecutorError::ExecutionFailed(
+            format!("Value at '{}' in the response is not an array", pointer)
+        )),
+        None => Err(ExecutorError::ExecutionFailed(
+            format!("Records not found at '{}' in the response", pointer)
+        )),
+    }
+}
+
+/// Value of a column in a record
+fn field_value<'a>(record: &'a Value, column: &str, fields: &HashMap<String, String>) -> &'a Value {
+    let value = match fields.get(column) {
+        Some(pointer) => record.pointer(pointer),
+        None => record.get(column),
+    };
+    value.unwrap_or(&Value::Null)
+}
+
+#[async_trait]
+impl Executor for HttpExecutor {
+    fn description(&self) -> &str {
+        "Load records returned by a paginated REST API into a registered data table"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["url", "data_table_id"],
+            "properties": {
+                "url": { "type": "string" },
+                "method": { "type": "string", "enum": ["GET", "POST"], "default": "GET" },
+                "headers": { "type": "object", "description": "Request headers, e.g. Authorization" },
+                "params": { "type": "object", "description": "Query string parameters" },
+                "body": { "description": "JSON body of POST requests" },
+                "records_pointer": { "type": "string", "default": "", "description": "JSON pointer to the records array, e.g. /data/items" },
+                "pagination": {
+                    "type": "object",
+                    "description": "{\"type\": \"page\", \"param\": \"page\", \"start\": 1, \"size_param\": \"page_size\", \"size\": 100} or {\"type\": \"cursor\", \"param\": \"cursor\", \"pointer\": \"/next_cursor\"}"
+                },
+                "max_pages": { "type": "integer", "default": 1000 },
+                "request_timeout_seconds": { "type": "integer", "default": 60 },
+                "data_table_id": { "type": "string", "description": "Target data table; its columns define the loaded fields" },
+                "fields": { "type": "object", "description": "JSON pointer into a record by column name" },
+                "partition": { "type": "object", "description": "Values of the partition columns, e.g. {\"dt\": \"${dt}\"}" },
+                "mode": { "type": "string", "enum": ["append", "truncate", "replace_partition"], "default": "append" }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        metadata: TaskMetadata,
+        context: ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Render template variables before reading the config
+        let config: HttpConfig = serde_json::from_value(metadata.render_config()?)
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid HTTP config: {}", e)))?;
+
+        let table = context.data_tables.get_with_details(config.data_table_id.clone())
+            .await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Data table {}: {}", config.data_table_id, e)))?;
+        // Tables of other tenants are not found
+        if metadata.tenant_id.as_deref() != Some(table.table.tenant_id.as_str()) {
+            return Err(ExecutorError::ExecutionFailed(
+                format!("Data table {}: Resource not found", config.data_table_id)
+            ));
+        }
+        if table.columns.is_empty() {
+            return Err(ExecutorError::ExecutionFailed(
+                format!("Data table {} has no columns defined", table.table.name)
+            ));
+        }
+
+        let partition = column_values(&config.partition);
+        let columns: Vec<String> = table.columns
+            .iter()
+            .filter(|c| !c.partitioner)
+            .map(|c| c.name.clone())
+            .collect();
+        for column in table.columns.iter().filter(|c| c.partitioner) {
+            if !partition.iter().any(|(name, _)| *name == column.name) {
+                return Err(ExecutorError::ExecutionFailed(
+                    format!("Missing partition value for column: {}", column.name)
+                ));
+            }
+        }
+
+        let data_source = common::find_data_source(&context.data_sources, &table.table.data_source_id)?;
+        let db = cancel::connect_for_run(&context.pools, data_source).await?;
+        let backend = db.get_database_backend();
+        let loader = TableLoader::new(backend, &table.table.name, columns.clone(), partition);
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.request_timeout_seconds.max(1)))
+            .build()
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to create HTTP client: {}", e)))?;
+
+        let txn = cancel::begin_for_run(&db, &context.cancel).await?;
+
+        // All pages are loaded in one transaction, so a failed run leaves the table untouched
+        context.changes.record(data_source, &table.table.name);
+        let rows_deleted = loader.prepare(&txn, config.mode).await?;
+
+        let mut page_params = config.pagination.first();
+        let mut pages = 0;
+        let mut rows_imported = 0;
+
+        loop {
+            if pages >= config.max_pages {
+                return Err(ExecutorError::ExecutionFailed(
+                    format!("More than {} pages returned, raise 'max_pages' if expected", config.max_pages)
+                ));
+            }
+
+            let response = fetch_page(&client, &config, &page_params).await?;
+            let records = extract_records(&response, &config.records_pointer)?;
+
+            let rows: Vec<Vec<Option<String>>> = records
+                .iter()
+                .map(|record| {
+                    columns
+                        .iter()
+                        .map(|column| value_text(field_value(record, column, &config.fields), backend))
+                        .collect()
+                })
+                .collect();
+            rows_imported += loader.insert_batch(&txn, &rows).await?;
+            pages += 1;
+
+            context.progress.report(serde_json::json!({
+                "pages_fetched": pages,
+                "rows_imported": rows_imported,
+            }));
+
+            match config.pagination.next(pages - 1, &response, records.len()) {
+                Some(params) => page_params = params,
+                None => break,
+            }
+        }
+
+        txn.commit().await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(ExecutionResult {
+            success: true,
+            message: format!("Loaded {} rows from {} pages into {}", rows_imported, pages, table.table.name),
+            data: Some(serde_json::json!({
+                "table": table.table.name,
+                "pages_fetched": pages,
+                "rows_imported": rows_imported,
+                "rows_deleted": rows_deleted,
+            })),
+            chain: Vec::new(),
+        })
+    }
+}
+
+/// Request one page; network errors and server errors are transient
+async fn fetch_page(
+    client: &reqwest::Client,
+    config: &HttpConfig,
+    page_params: &[(String, String)],
+) -> Result<Value, ExecutorError> {
+    let mut request = match config.method.to_uppercase().as_str() {
+        "GET" => client.get(&config.url),
+        "POST" => client.post(&config.url),
+        other => return Err(ExecutorError::ExecutionFailed(format!("Unsupported HTTP method: {}", other))),
+    };
+
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+    request = request.query(&config.params).query(page_params);
+    if let Some(body) = &config.body {
+        request = request.json(body);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ExecutorError::DownloadFailed(format!("Request to {} failed: {}", config.url, e)))?;
+
+    let status = response.status();
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(ExecutorError::DownloadFailed(format!("Request failed with status: {}", status)));
+    }
+    if !status.is_success() {
+        return Err(ExecutorError::ExecutionFailed(format!("Request failed with status: {}", status)));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid JSON response: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pagination() {
+        let response = serde_json::json!({
+            "data": { "items": [{ "shop": { "name": "A" }, "sales": 10 }] },
+            "meta": { "next_cursor": "c2" }
+        });
+
+        let records = extract_records(&response, "/data/items").unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(extract_records(&response, "/meta").is_err());
+
+        let fields = HashMap::from([("shop_name".to_string(), "/shop/name".to_string())]);
+        assert_eq!(field_value(&records[0], "shop_name", &fields), &serde_json::json!("A"));
+        assert_eq!(field_value(&records[0], "sales", &fields), &serde_json::json!(10));
+        assert_eq!(field_value(&records[0], "missing", &fields), &Value::Null);
+
+        // Pages stop on a short page
+        let page: Pagination = serde_json::from_value(serde_json::json!({
+            "type": "page", "size_param": "page_size", "size": 2
+        })).unwrap();
+        assert_eq!(page.first(), vec![
+            ("page".to_string(), "1".to_string()),
+            ("page_size".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(page.next(0, &response, 2).unwrap()[0], ("page".to_string(), "2".to_string()));
+        assert_eq!(page.next(1, &response, 1), None);
+
+        // Cursors stop when the response has none
+        let cursor: Pagination = serde_json::from_value(serde_json::json!({
+            "type": "cursor", "pointer": "/meta/next_cursor"
+        })).unwrap();
+        assert_eq!(cursor.next(0, &response, 1), Some(vec![("cursor".to_string(), "c2".to_string())]));
+        assert_eq!(cursor.next(1, &serde_json::json!({ "meta": {} }), 1), None);
+    }
+}
@@ -0,0 +1,17 @@
+mod common;
+mod copy;
+mod csv;
+mod excel;
+mod export;
+mod http;
+mod quality;
+mod sql;
+mod table_loader;
+
+pub use copy::CopyExecutor;
+pub use self::csv::CsvExecutor;
+pub use excel::ExcelExecutor;
+pub use export::ExportExecutor;
+pub use http::HttpExecutor;
+pub use quality::QualityExecutor;
+pub use sql::SqlExecutor;
@@ -0,0 +1,492 @@
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseTransaction, Statement, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::domain::error::ExecutorError;
+use super::common;
+use super::table_loader::{column_values, quote_identifier, quote_literal};
+use super::super::{cancel, template, Executor, ExecutorContext, ExecutionResult, ProgressReporter, TaskMetadata};
+
+// Quality Executor - runs assertion checks against a table partition
+pub struct QualityExecutor;
+
+// Options of a quality task
+#[derive(Debug, Clone, Deserialize)]
+struct QualityConfig {
+    data_source_id: String,
+    table: String,
+    // Partition under check, e.g. {"dt": "${dt}"}; the whole table when empty
+    #[serde(default)]
+    partition: serde_json::Map<String, Value>,
+    checks: Vec<Check>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Check {
+    // Name in the results, the check type by default
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    severity: Severity,
+    #[serde(flatten)]
+    kind: CheckKind,
+}
+
+// A failed `warn` check is reported without failing the task
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Severity {
+    #[default]
+    Error,
+    Warn,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum CheckKind {
+    // Number of rows within [min, max]
+    RowCount {
+        #[serde(default = "default_min_rows")]
+        min: i64,
+        #[serde(default)]
+        max: Option<i64>,
+    },
+    // No NULL in the columns
+    NotNull { columns: Vec<String> },
+    // No duplicate combination of the key columns
+    Unique { columns: Vec<String> },
+    // Values of a column within [min, max]; numbers, or strings such as dates
+    Range {
+        column: String,
+        #[serde(default)]
+        min: Option<Value>,
+        #[serde(default)]
+        max: Option<Value>,
+    },
+    // Relative change of the row count versus the previous partition
+    RowCountDrift {
+        max_change_ratio: f64,
+        #[serde(default = "default_partition_column")]
+        partition_column: String,
+        // Partition compared with, the day before by default, e.g. "${dt-7}"
+        #[serde(default)]
+        previous: Option<String>,
+    },
+}
+
+fn default_min_rows() -> i64 {
+    1
+}
+
+fn default_partition_column() -> String {
+    "dt".to_string()
+}
+
+impl CheckKind {
+    fn type_name(&self) -> &'static str {
+        match self {
+            CheckKind::RowCount { .. } => "row_count",
+            CheckKind::NotNull { .. } => "not_null",
+            CheckKind::Unique { .. } => "unique",
+            CheckKind::Range { .. } => "range",
+            CheckKind::RowCountDrift { .. } => "row_count_drift",
+        }
+    }
+}
+
+// Outcome of a check, reported under `checks` in the result data
+#[derive(Debug, Clone, Serialize)]
+struct CheckResult {
+    name: String,
+    #[serde(rename = "type")]
+    check_type: &'static str,
+    severity: Severity,
+    passed: bool,
+    message: String,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    observed: Value,
+}
+
+// Builds the statements of the checks against one table partition
+struct CheckQueries {
+    backend: DatabaseBackend,
+    table: String,
+    partition: Vec<(String, String)>,
+}
+
+impl CheckQueries {
+    /// Count the rows of the partition matching an optional condition
+    fn count(&self, condition: Option<&str>) -> String {
+        self.count_in(&self.partition, condition)
+    }
+
+    fn count_in(&self, partition: &[(String, String)], condition: Option<&str>) -> String {
+        let mut conditions: Vec<String> = partition
+            .iter()
+            .map(|(column, value)| format!(
+                "{} = {}",
+                quote_identifier(self.backend, column),
+                quote_literal(self.backend, Some(value))
+            ))
+            .collect();
+        conditions.extend(condition.map(|c| format!("({})", c)));
+
+        let mut sql = format!("SELECT COUNT(*) AS value FROM {}", quote_identifier(self.backend, &self.table));
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql
+    }
+
+    /// Count the key combinations occurring more than once
+    fn duplicates(&self, columns: &[String]) -> String {
+        let keys: Vec<String> = columns.iter().map(|c| quote_identifier(self.backend, c)).collect();
+        let rows = self.count(None).replacen("COUNT(*) AS value", &keys.join(", "), 1);
+        format!(
+            "SELECT COUNT(*) AS value FROM ({} GROUP BY {} HAVING COUNT(*) > 1) duplicates",
+            rows,
+            keys.join(", ")
+        )
+    }
+
+    /// Condition matching values outside of a range
+    fn out_of_range(&self, column: &str, min: Option<&Value>, max: Option<&Value>) -> Result<String, ExecutorError> {
+        let column = quote_identifier(self.backend, column);
+        let mut conditions = Vec::new();
+        if let Some(min) = min {
+            conditions.push(format!("{} < {}", column, self.bound(min)?));
+        }
+        if let Some(max) = max {
+            conditions.push(format!("{} > {}", column, self.bound(max)?));
+        }
+        if conditions.is_empty() {
+            return Err(ExecutorError::ExecutionFailed("Range check needs 'min' or 'max'".to_string()));
+        }
+        Ok(conditions.join(" OR "))
+    }
+
+    fn bound(&self, value: &Value) -> Result<String, ExecutorError> {
+        match value {
+            Value::Number(n) => Ok(n.to_string()),
+            Value::String(s) => Ok(quote_literal(self.backend, Some(s))),
+            other => Err(ExecutorError::ExecutionFailed(format!("Invalid range bound: {}", other))),
+        }
+    }
+
+    /// The partition with its `column` replaced by the previous value
+    fn previous_partition(&self, column: &str, previous: Option<&str>) -> Result<Vec<(String, String)>, ExecutorError> {
+        let current = self.partition
+            .iter()
+            .find(|(name, _)| name == column)
+            .map(|(_, value)| value.as_str())
+            .ok_or_else(|| ExecutorError::ExecutionFailed(
+                format!("Drift check needs a value for partition column '{}'", column)
+            ))?;
+
+        let previous = match previous {
+            Some(previous) => previous.to_string(),
+            None => {
+                let date = template::parse_logical_date(current)?;
+                let format = if current.contains('-') { "%Y-%m-%d" } else { "%Y%m%d" };
+                (date - chrono::Duration::days(1)).format(format).to_string()
+            }
+        };
+
+        Ok(self.partition
+            .iter()
+            .map(|(name, value)| {
+                let value = if name == column { previous.clone() } else { value.clone() };
+                (name.clone(), value)
+            })
+            .collect())
+    }
+}
+
+/// Relative change between two row counts, `None` without previous rows
+fn drift_ratio(current: i64, previous: i64) -> Option<f64> {
+    (previous > 0).then(|| (current - previous).abs() as f64 / previous as f64)
+}
+
+#[async_trait]
+impl Executor for QualityExecutor {
+    fn description(&self) -> &str {
+        "Run data quality checks against a table partition; fails when a check fails"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["data_source_id", "table", "checks"],
+            "properties": {
+                "data_source_id": { "type": "string" },
+                "table": { "type": "string", "description": "Table under check, optionally schema-qualified" },
+                "partition": { "type": "object", "description": "Partition under check, e.g. {\"dt\": \"${dt}\"}" },
+                "checks": {
+                    "type": "array",
+                    "description": "Checks with a type of row_count (min, max), not_null (columns), unique (columns), range (column, min, max) or row_count_drift (max_change_ratio, partition_column, previous); optional name and severity (error / warn)",
+                    "items": { "type": "object", "required": ["type"] }
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        metadata: TaskMetadata,
+        context: ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Render template variables before reading the config
+        let config: QualityConfig = serde_json::from_value(metadata.render_config()?)
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid quality config: {}", e)))?;
+
+        let data_source = common::find_data_source(&context.data_sources, &config.data_source_id)?;
+        let db = cancel::connect_for_run(&context.pools, data_source).await?;
+        let backend = db.get_database_backend();
+        // Checks read inside a transaction so that a cancelled run stops the running check
+        let txn = cancel::begin_for_run(&db, &context.cancel).await?;
+
+        let queries = CheckQueries {
+            backend,
+            table: config.table.clone(),
+            partition: column_values(&config.partition),
+        };
+
+        let results = run_checks(&txn, &queries, &config.checks, &context.progress).await?;
+
+        txn.commit().await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to commit transaction: {}", e)))?;
+
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|r| !r.passed && r.severity == Severity::Error)
+            .map(|r| r.name.as_str())
+            .collect();
+        let message = if failed.is_empty() {
+            format!("All {} checks passed on {}", results.len(), config.table)
+        } else {
+            format!("{} of {} checks failed on {}: {}", failed.len(), results.len(), config.table, failed.join(", "))
+        };
+
+        Ok(ExecutionResult {
+            success: failed.is_empty(),
+            message,
+            data: Some(serde_json::json!({
+                "table": config.table,
+                "partition": config.partition,
+                "checks": results,
+            })),
+            chain: Vec::new(),
+        })
+    }
+}
+
+/// Run the checks in order, each one in a savepoint of the transaction
+///
+/// On PostgreSQL a failed statement aborts the transaction; rolling back to the
+/// savepoint of the check keeps the transaction usable for the next checks.
+async fn run_checks(
+    txn: &DatabaseTransaction,
+    queries: &CheckQueries,
+    checks: &[Check],
+    progress: &ProgressReporter,
+) -> Result<Vec<CheckResult>, ExecutorError> {
+    let mut results = Vec::new();
+    for (index, check) in checks.iter().enumerate() {
+        let savepoint = txn.begin()
+            .await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to create savepoint: {}", e)))?;
+        let (passed, message, observed) = match run_check(&savepoint, queries, &check.kind).await {
+            Ok(outcome) => {
+                savepoint.commit()
+                    .await
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to release savepoint: {}", e)))?;
+                outcome
+            }
+            // A check that cannot run (e.g. an unknown column) fails
+            Err(e) => {
+                savepoint.rollback()
+                    .await
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to roll back savepoint: {}", e)))?;
+                (false, e.to_string(), Value::Null)
+            }
+        };
+        results.push(CheckResult {
+            name: check.name.clone().unwrap_or_else(|| check.kind.type_name().to_string()),
+            check_type: check.kind.type_name(),
+            severity: check.severity,
+            passed,
+            message,
+            observed,
+        });
+
+        progress.report(serde_json::json!({
+            "checks_completed": index + 1,
+            "check_count": checks.len(),
+        }));
+    }
+    Ok(results)
+}
+
+/// Run one check, returning whether it passed, a message and the observed values
+async fn run_check<C: ConnectionTrait>(
+    db: &C,
+    queries: &CheckQueries,
+    check: &CheckKind,
+) -> Result<(bool, String, Value), ExecutorError> {
+    match check {
+        CheckKind::RowCount { min, max } => {
+            let count = count(db, queries.backend, queries.count(None)).await?;
+            let passed = count >= *min && max.is_none_or(|max| count <= max);
+            let expected = match max {
+                Some(max) => format!("between {} and {}", min, max),
+                None => format!("at least {}", min),
+            };
+            Ok((passed, format!("{} rows, expected {}", count, expected), serde_json::json!({ "row_count": count })))
+        }
+        CheckKind::NotNull { columns } => {
+            let mut nulls = serde_json::Map::new();
+            for column in columns {
+                let condition = format!("{} IS NULL", quote_identifier(queries.backend, column));
+                let count = count(db, queries.backend, queries.count(Some(&condition))).await?;
+                nulls.insert(column.clone(), count.into());
+            }
+            let offending: Vec<&String> = nulls.iter().filter(|(_, n)| n.as_i64() != Some(0)).map(|(c, _)| c).collect();
+            let message = if offending.is_empty() {
+                "No NULL values".to_string()
+            } else {
+                format!("NULL values in {}", offending.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "))
+            };
+            Ok((offending.is_empty(), message, serde_json::json!({ "null_counts": nulls })))
+        }
+        CheckKind::Unique { columns } => {
+            let duplicates = count(db, queries.backend, queries.duplicates(columns)).await?;
+            Ok((
+                duplicates == 0,
+                format!("{} duplicate keys on ({})", duplicates, columns.join(", ")),
+                serde_json::json!({ "duplicate_keys": duplicates }),
+            ))
+        }
+        CheckKind::Range { column, min, max } => {
+            let condition = queries.out_of_range(column, min.as_ref(), max.as_ref())?;
+            let outside = count(db, queries.backend, queries.count(Some(&condition))).await?;
+            Ok((
+                outside == 0,
+                format!("{} values of {} out of range", outside, column),
+                serde_json::json!({ "out_of_range": outside }),
+            ))
+        }
+        CheckKind::RowCountDrift { max_change_ratio, partition_column, previous } => {
+            let previous_partition = queries.previous_partition(partition_column, previous.as_deref())?;
+            let current = count(db, queries.backend, queries.count(None)).await?;
+            let previous = count(db, queries.backend, queries.count_in(&previous_partition, None)).await?;
+            let observed = serde_json::json!({ "row_count": current, "previous_row_count": previous });
+
+            match drift_ratio(current, previous) {
+                Some(ratio) => Ok((
+                    ratio <= *max_change_ratio,
+                    format!("Row count changed by {:.1}% ({} -> {}), allowed {:.1}%", ratio * 100.0, previous, current, max_change_ratio * 100.0),
+                    observed,
+                )),
+                // Nothing to compare with, e.g. the first partition
+                None => Ok((true, "Previous partition is empty, drift not checked".to_string(), observed)),
+            }
+        }
+    }
+}
+
+async fn count<C: ConnectionTrait>(db: &C, backend: DatabaseBackend, sql: String) -> Result<i64, ExecutorError> {
+    let row = db.query_one(Statement::from_string(backend, sql))
+        .await
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Check query failed: {}", e)))?
+        .ok_or_else(|| ExecutorError::ExecutionFailed("Check query returned no row".to_string()))?;
+
+    row.try_get::<i64>("", "value")
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to read check result: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_queries() {
+        let queries = CheckQueries {
+            backend: DatabaseBackend::Postgres,
+            table: "dws.orders".to_string(),
+            partition: vec![("dt".to_string(), "20251018".to_string())],
+        };
+
+        assert_eq!(
+            queries.count(Some("\"id\" IS NULL")),
+            "SELECT COUNT(*) AS value FROM \"dws\".\"orders\" WHERE \"dt\" = '20251018' AND (\"id\" IS NULL)"
+        );
+        assert_eq!(
+            queries.duplicates(&["shop_id".to_string(), "sku".to_string()]),
+            "SELECT COUNT(*) AS value FROM (SELECT \"shop_id\", \"sku\" FROM \"dws\".\"orders\" \
+             WHERE \"dt\" = '20251018' GROUP BY \"shop_id\", \"sku\" HAVING COUNT(*) > 1) duplicates"
+        );
+        assert_eq!(
+            queries.out_of_range("amount", Some(&serde_json::json!(0)), None).unwrap(),
+            "\"amount\" < 0"
+        );
+        assert_eq!(
+            queries.previous_partition("dt", None).unwrap(),
+            vec![("dt".to_string(), "20251017".to_string())]
+        );
+
+        assert_eq!(drift_ratio(150, 100), Some(0.5));
+        assert_eq!(drift_ratio(10, 0), None);
+
+        let check: Check = serde_json::from_value(serde_json::json!({
+            "type": "row_count_drift", "max_change_ratio": 0.3, "severity": "warn"
+        })).unwrap();
+        assert_eq!(check.severity, Severity::Warn);
+        assert_eq!(check.kind.type_name(), "row_count_drift");
+    }
+
+    #[tokio::test]
+    async fn test_failed_check_keeps_transaction() {
+        use sea_orm::{DbErr, MockDatabase, Transaction};
+        use std::collections::BTreeMap;
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_errors([DbErr::Custom("column \"missing\" does not exist".to_string())])
+            .append_query_results([[BTreeMap::from([("value", sea_orm::Value::from(42i64))])]])
+            .into_connection();
+        let queries = CheckQueries {
+            backend: DatabaseBackend::Postgres,
+            table: "orders".to_string(),
+            partition: Vec::new(),
+        };
+        let checks: Vec<Check> = serde_json::from_value(serde_json::json!([
+            { "type": "not_null", "columns": ["missing"] },
+            { "type": "row_count", "min": 1 },
+        ])).unwrap();
+
+        let txn = db.begin().await.unwrap();
+        let results = run_checks(&txn, &queries, &checks, &ProgressReporter::default()).await.unwrap();
+        txn.commit().await.unwrap();
+
+        assert!(!results[0].passed);
+        assert!(results[0].message.contains("missing"));
+        assert!(results[1].passed, "{}", results[1].message);
+
+        // The failed check is rolled back to its savepoint before the next one runs
+        let statements: Vec<String> = db.into_transaction_log()
+            .iter()
+            .flat_map(Transaction::statements)
+            .map(|statement| statement.sql.clone())
+            .collect();
+        assert_eq!(statements, vec![
+            "BEGIN",
+            "SAVEPOINT savepoint_1",
+            "SELECT COUNT(*) AS value FROM \"orders\" WHERE (\"missing\" IS NULL)",
+            "ROLLBACK TO SAVEPOINT savepoint_1",
+            "SAVEPOINT savepoint_1",
+            "SELECT COUNT(*) AS value FROM \"orders\"",
+            "RELEASE SAVEPOINT savepoint_1",
+            "COMMIT",
+        ]);
+    }
+}
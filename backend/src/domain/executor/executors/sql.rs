@@ -0,0 +1,222 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde::Serialize;
+use crate::domain::error::ExecutorError;
+use crate::entities::data_source;
+use super::common;
+use super::super::{cancel, sql_script, Executor, ExecutorContext, ExecutionResult, TaskMetadata};
+
+// SQL Executor - executes SQL statements against data sources
+pub struct SqlExecutor;
+
+#[async_trait]
+impl Executor for SqlExecutor {
+    fn description(&self) -> &str {
+        "Run a SQL script against a data source, statement by statement"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["data_source_id", "sql"],
+            "properties": {
+                "data_source_id": { "type": "string", "description": "Target data source" },
+                "sql": { "type": "string", "description": "SQL script, statements separated by ';'" },
+                "transactional": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "Run all statements in one transaction"
+                },
+                "statement_results": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "Include per-statement outcomes in the result data"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        metadata: TaskMetadata,
+        context: ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Render template variables before reading the config
+        let config = metadata.render_config()?;
+
+        // Extract SQL configuration from metadata
+        let sql_statement = config
+            .get("sql")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ExecutorError::ExecutionFailed("Missing 'sql' in config".to_string()))?;
+
+        let data_source_id = config
+            .get("data_source_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ExecutorError::ExecutionFailed("Missing 'data_source_id' in config".to_string()))?;
+
+        let data_source = common::find_data_source(&context.data_sources, data_source_id)?;
+
+        let options = ScriptOptions {
+            transactional: config
+                .get("transactional")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            statement_results: config
+                .get("statement_results")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+        };
+
+        self.execute_script(data_source, sql_statement, &options, &context).await
+    }
+}
+
+// Options for running a SQL script
+struct ScriptOptions {
+    // Run all statements in a single transaction and roll back on the first failure
+    transactional: bool,
+    // Include per-statement outcomes in the result data
+    statement_results: bool,
+}
+
+// Outcome of a single statement of a SQL script
+#[derive(Debug, Clone, Serialize)]
+struct StatementOutcome {
+    index: usize,
+    sql: String,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows_affected: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl SqlExecutor {
+    /// Split a SQL script into statements and execute them in order.
+    ///
+    /// Execution stops at the first failing statement; in transactional mode all
+    /// previous statements are rolled back. Note that MySQL commits DDL implicitly.
+    async fn execute_script(
+        &self,
+        data_source: &data_source::Model,
+        sql: &str,
+        options: &ScriptOptions,
+        context: &ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        use sea_orm::{Statement, ConnectionTrait};
+
+        let statements = sql_script::split_statements(sql);
+        if statements.is_empty() {
+            return Err(ExecutorError::ExecutionFailed("SQL script contains no statements".to_string()));
+        }
+
+        // Transactional scripts run on the shared pool; other scripts get a connection of
+        // their own, so that session state (SET, temporary tables) carries over
+        let (db, txn) = if options.transactional {
+            let db = cancel::connect_for_run(&context.pools, data_source).await?;
+            let txn = cancel::begin_for_run(&db, &context.cancel).await?;
+            (db, Some(txn))
+        } else {
+            (Arc::new(cancel::connect_dedicated(&context.pools, data_source, &context.cancel).await?), None)
+        };
+        let backend = db.get_database_backend();
+
+        let mut outcomes: Vec<StatementOutcome> = statements.iter()
+            .enumerate()
+            .map(|(index, statement)| StatementOutcome {
+                index,
+                sql: statement.chars().take(200).collect(),
+                status: "skipped",
+                rows_affected: None,
+                error: None,
+            })
+            .collect();
+
+        let mut rows_affected = 0;
+        let mut failure = None;
+
+        // Execute statements one by one, stopping at the first failure
+        for (index, statement) in statements.iter().enumerate() {
+            if let Some(table) = sql_script::written_table(statement) {
+                context.changes.record(data_source, &table);
+            }
+            let stmt = Statement::from_string(backend, statement.clone());
+            let result = match &txn {
+                Some(txn) => txn.execute(stmt).await,
+                None => db.execute(stmt).await,
+            };
+
+            match result {
+                Ok(result) => {
+                    rows_affected += result.rows_affected();
+                    outcomes[index].status = "succeeded";
+                    outcomes[index].rows_affected = Some(result.rows_affected());
+                    context.progress.report(serde_json::json!({
+                        "statements_completed": index + 1,
+                        "statement_count": statements.len(),
+                        "rows_affected": rows_affected,
+                    }));
+                }
+                Err(e) => {
+                    outcomes[index].status = "failed";
+                    outcomes[index].error = Some(e.to_string());
+                    failure = Some((index, e.to_string()));
+                    break;
+                }
+            }
+        }
+
+        let message = match (&failure, txn) {
+            (None, Some(txn)) => {
+                txn.commit().await
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to commit transaction: {}", e)))?;
+                format!(
+                    "SQL script executed successfully. Statements: {}, rows affected: {}",
+                    statements.len(), rows_affected
+                )
+            }
+            (None, None) => format!(
+                "SQL script executed successfully. Statements: {}, rows affected: {}",
+                statements.len(), rows_affected
+            ),
+            (Some((index, error)), Some(txn)) => {
+                txn.rollback().await
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to roll back transaction: {}", e)))?;
+                for outcome in outcomes.iter_mut().filter(|o| o.status == "succeeded") {
+                    outcome.status = "rolled_back";
+                }
+                format!(
+                    "SQL execution failed at statement {} of {}: {}. Transaction rolled back",
+                    index + 1, statements.len(), error
+                )
+            }
+            (Some((index, error)), None) => format!(
+                "SQL execution failed at statement {} of {}: {}",
+                index + 1, statements.len(), error
+            ),
+        };
+
+        // Close the dedicated connection, the pool stays open for later runs
+        if let (false, Some(db)) = (options.transactional, Arc::into_inner(db)) {
+            db.close().await
+                .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to close connection: {}", e)))?;
+        }
+
+        let mut data = serde_json::json!({
+            "rows_affected": rows_affected,
+            "statement_count": statements.len(),
+        });
+        if options.statement_results {
+            data["statements"] = serde_json::to_value(&outcomes)
+                .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to serialize outcomes: {}", e)))?;
+        }
+
+        Ok(ExecutionResult {
+            success: failure.is_none(),
+            message,
+            data: Some(data),
+            chain: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,303 @@
+// Batched loading of rows into an existing table, shared by the import executors
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use serde::Deserialize;
+use crate::domain::error::ExecutorError;
+
+// How the rows already in the target table are handled before loading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadMode {
+    // Keep existing rows
+    #[default]
+    Append,
+    // Delete every existing row
+    #[serde(alias = "overwrite")]
+    Truncate,
+    // Delete the rows of the partition, by default the constant columns (e.g. dt)
+    #[serde(alias = "overwrite_partition")]
+    ReplacePartition,
+}
+
+// Portable column types, used to map column types between databases and to
+// type the columns of imported files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    Boolean,
+    #[serde(alias = "smallint")]
+    SmallInt,
+    Integer,
+    #[serde(alias = "bigint")]
+    BigInt,
+    Real,
+    Double,
+    Decimal,
+    Date,
+    Time,
+    Timestamp,
+    #[serde(alias = "timestamptz")]
+    TimestampTz,
+    Json,
+    Uuid,
+    #[default]
+    Text,
+}
+
+impl ColumnKind {
+    /// Kind of a column from the type name reported by the source database
+    pub fn from_type_name(name: &str) -> Self {
+        let name = name.to_uppercase();
+        match name.as_str() {
+            "BOOL" | "BOOLEAN" => Self::Boolean,
+            "INT2" | "TINYINT" | "SMALLINT" | "TINYINT UNSIGNED" => Self::SmallInt,
+            "INT4" | "INT" | "INTEGER" | "MEDIUMINT" | "YEAR" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" => Self::Integer,
+            "INT8" | "BIGINT" | "INT UNSIGNED" => Self::BigInt,
+            "FLOAT4" | "FLOAT" => Self::Real,
+            "FLOAT8" | "DOUBLE" => Self::Double,
+            "NUMERIC" | "DECIMAL" | "BIGINT UNSIGNED" => Self::Decimal,
+            "DATE" => Self::Date,
+            "TIME" => Self::Time,
+            "TIMESTAMP" | "DATETIME" => Self::Timestamp,
+            "TIMESTAMPTZ" => Self::TimestampTz,
+            "JSON" | "JSONB" => Self::Json,
+            "UUID" => Self::Uuid,
+            _ => Self::Text,
+        }
+    }
+
+    /// Column type on the target database
+    pub fn sql_type(self, backend: DatabaseBackend) -> &'static str {
+        match backend {
+            DatabaseBackend::MySql => match self {
+                Self::Boolean => "BOOLEAN",
+                Self::SmallInt => "SMALLINT",
+                Self::Integer => "INT",
+                Self::BigInt => "BIGINT",
+                Self::Real => "FLOAT",
+                Self::Double => "DOUBLE",
+                Self::Decimal => "DECIMAL(38, 10)",
+                Self::Date => "DATE",
+                Self::Time => "TIME",
+                Self::Timestamp | Self::TimestampTz => "DATETIME(6)",
+                Self::Json => "JSON",
+                Self::Uuid => "CHAR(36)",
+                Self::Text => "TEXT",
+            },
+            _ => match self {
+                Self::Boolean => "BOOLEAN",
+                Self::SmallInt => "SMALLINT",
+                Self::Integer => "INTEGER",
+                Self::BigInt => "BIGINT",
+                Self::Real => "REAL",
+                Self::Double => "DOUBLE PRECISION",
+                Self::Decimal => "NUMERIC",
+                Self::Date => "DATE",
+                Self::Time => "TIME",
+                Self::Timestamp => "TIMESTAMP",
+                Self::TimestampTz => "TIMESTAMPTZ",
+                Self::Json => "JSONB",
+                Self::Uuid => "UUID",
+                Self::Text => "TEXT",
+            },
+        }
+    }
+}
+
+// TableLoader - inserts rows into a table, with constant columns appended to every row
+pub struct TableLoader {
+    backend: DatabaseBackend,
+    table: String,
+    columns: Vec<String>,
+    constants: Vec<(String, String)>,
+    // Column values identifying the partition replaced by `LoadMode::ReplacePartition`
+    partition: Vec<(String, String)>,
+}
+
+impl TableLoader {
+    pub fn new(
+        backend: DatabaseBackend,
+        table: &str,
+        columns: Vec<String>,
+        constants: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            backend,
+            table: table.to_string(),
+            columns,
+            partition: constants.clone(),
+            constants,
+        }
+    }
+
+    /// Use a partition other than the constant columns, e.g. when the rows carry their dt
+    pub fn with_partition(mut self, partition: Vec<(String, String)>) -> Self {
+        self.partition = partition;
+        self
+    }
+
+    /// Clear the target table according to the load mode
+    pub async fn prepare<C: ConnectionTrait>(&self, db: &C, mode: LoadMode) -> Result<u64, ExecutorError> {
+        let sql = match mode {
+            LoadMode::Append => return Ok(0),
+            LoadMode::Truncate => format!("DELETE FROM {}", quote_identifier(self.backend, &self.table)),
+            LoadMode::ReplacePartition => {
+                if self.partition.is_empty() {
+                    return Err(ExecutorError::ExecutionFailed(
+                        "Mode 'replace_partition' requires partition columns".to_string()
+                    ));
+                }
+                let conditions: Vec<String> = self.partition
+                    .iter()
+                    .map(|(column, value)| format!(
+                        "{} = {}",
+                        quote_identifier(self.backend, column),
+                        quote_literal(self.backend, Some(value))
+                    ))
+                    .collect();
+                format!(
+                    "DELETE FROM {} WHERE {}",
+                    quote_identifier(self.backend, &self.table),
+                    conditions.join(" AND ")
+                )
+            }
+        };
+
+        let result = db.execute(Statement::from_string(self.backend, sql))
+            .await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to clear table {}: {}", self.table, e)))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Insert a batch of rows with a single statement
+    pub async fn insert_batch<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        rows: &[Vec<Option<String>>],
+    ) -> Result<u64, ExecutorError> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let result = db.execute(Statement::from_string(self.backend, self.insert_sql(rows)))
+            .await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to insert into {}: {}", self.table, e)))?;
+        Ok(result.rows_affected())
+    }
+
+    fn insert_sql(&self, rows: &[Vec<Option<String>>]) -> String {
+        let columns: Vec<String> = self.columns
+            .iter()
+            .chain(self.constants.iter().map(|(column, _)| column))
+            .map(|column| quote_identifier(self.backend, column))
+            .collect();
+
+        let values: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                // Short rows are padded with NULL, extra values are dropped
+                let literals: Vec<String> = (0..self.columns.len())
+                    .map(|i| quote_literal(self.backend, row.get(i).and_then(|v| v.as_deref())))
+                    .chain(self.constants.iter().map(|(_, value)| quote_literal(self.backend, Some(value))))
+                    .collect();
+                format!("({})", literals.join(", "))
+            })
+            .collect();
+
+        format!(
+            "INSERT INTO {} ({}) VALUES {}",
+            quote_identifier(self.backend, &self.table),
+            columns.join(", "),
+            values.join(", ")
+        )
+    }
+}
+
+/// Column values configured as a JSON object, e.g. {"dt": "${dt}"}
+pub fn column_values(values: &serde_json::Map<String, serde_json::Value>) -> Vec<(String, String)> {
+    values
+        .iter()
+        .map(|(column, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (column.clone(), value)
+        })
+        .collect()
+}
+
+/// Text of a JSON value loaded into a column, `None` being NULL
+pub fn value_text(value: &serde_json::Value, backend: DatabaseBackend) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(b) => Some(match (backend, b) {
+            (DatabaseBackend::MySql, true) => "1".to_string(),
+            (DatabaseBackend::MySql, false) => "0".to_string(),
+            (_, b) => b.to_string(),
+        }),
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        // JSON columns, and arrays as JSON text
+        other => Some(other.to_string()),
+    }
+}
+
+/// Quote a possibly schema-qualified identifier
+pub fn quote_identifier(backend: DatabaseBackend, name: &str) -> String {
+    name.split('.')
+        .map(|part| match backend {
+            DatabaseBackend::MySql => format!("`{}`", part.replace('`', "``")),
+            _ => format!("\"{}\"", part.replace('"', "\"\"")),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Quote a value as a SQL string literal, `None` being NULL
+pub fn quote_literal(backend: DatabaseBackend, value: Option<&str>) -> String {
+    match value {
+        None => "NULL".to_string(),
+        Some(value) => {
+            let escaped = value.replace('\'', "''");
+            // MySQL also treats backslashes as escape characters
+            let escaped = match backend {
+                DatabaseBackend::MySql => escaped.replace('\\', "\\\\"),
+                _ => escaped,
+            };
+            format!("'{}'", escaped)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_sql() {
+        let loader = TableLoader::new(
+            DatabaseBackend::Postgres,
+            "ods.orders",
+            vec!["id".to_string(), "name".to_string()],
+            vec![("dt".to_string(), "20251018".to_string())],
+        );
+
+        let sql = loader.insert_sql(&[
+            vec![Some("1".to_string()), Some("O'Brien".to_string())],
+            vec![Some("2".to_string())],
+        ]);
+        assert_eq!(
+            sql,
+            "INSERT INTO \"ods\".\"orders\" (\"id\", \"name\", \"dt\") VALUES \
+             ('1', 'O''Brien', '20251018'), ('2', NULL, '20251018')"
+        );
+
+        assert_eq!(quote_identifier(DatabaseBackend::MySql, "orders"), "`orders`");
+        assert_eq!(quote_literal(DatabaseBackend::MySql, Some("a\\b")), "'a\\\\b'");
+
+        assert_eq!(value_text(&serde_json::json!(true), DatabaseBackend::MySql), Some("1".to_string()));
+        assert_eq!(value_text(&serde_json::json!(true), DatabaseBackend::Postgres), Some("true".to_string()));
+        assert_eq!(value_text(&serde_json::json!({"a": 1}), DatabaseBackend::Postgres), Some("{\"a\":1}".to_string()));
+        assert_eq!(value_text(&serde_json::Value::Null, DatabaseBackend::Postgres), None);
+    }
+}
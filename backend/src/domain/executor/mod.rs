@@ -0,0 +1,1176 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use crate::config::ExecutorConfig;
+use crate::entities::{data_source, storage, task_run};
+use super::error::ExecutorError;
+use super::connection_pool::ConnectionPools;
+use super::data_source::DataSourceService;
+use super::data_table::DataTableService;
+use super::query::QueryCache;
+use super::storage::StorageService;
+use super::task::{Task, TaskService, TASK_STATUS_COMPLETED, TASK_STATUS_FAILED};
+use super::task_run::{TaskRunService, TASK_RUN_STATUS_QUEUED, TASK_RUN_STATUS_RUNNING, TASK_RUN_STATUS_SKIPPED};
+
+mod backfill;
+mod cancel;
+pub mod executors;
+mod registry;
+mod retry;
+pub mod sql_script;
+pub mod template;
+
+pub use backfill::{BackfillRequest, BackfillOptions, BackfillResult};
+pub use cancel::CancelHandle;
+pub use registry::{ExecutorRegistry, ExecutorInfo};
+pub use retry::RetryPolicy;
+
+// Task metadata structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskMetadata {
+    pub task_id: String,
+    // Tenant the run belongs to; runs requested through the API take it from the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    // Business date (yyyyMMdd) the run is for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_date: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub next_actions: Vec<NextAction>,
+    pub config: serde_json::Value,
+    // User-supplied template params, referenced as ${name} in config
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, serde_json::Value>,
+    // Cron schedule picked up by the built-in scheduler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<TaskSchedule>,
+    // Retry policy applied when an attempt fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+impl TaskMetadata {
+    /// Render `${...}` template variables in the config for the logical date of this run
+    pub fn render_config(&self) -> Result<serde_json::Value, ExecutorError> {
+        let vars = template::TemplateVars::new(self.logical_date.as_deref(), &self.params)?;
+        template::render_value(&self.config, &vars)
+    }
+
+    /// Maximum execution time, from `timeout_seconds` in the config
+    pub fn timeout_seconds(&self) -> Option<u64> {
+        self.config
+            .get("timeout_seconds")
+            .and_then(|v| v.as_u64())
+            .filter(|seconds| *seconds > 0)
+    }
+}
+
+// Cron schedule of a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSchedule {
+    // 5-field (min hour dom mon dow) or 6-field (with seconds) cron expression, server local time
+    pub cron: String,
+    // Days added to the fire date to get the logical date, e.g. -1 to run for yesterday
+    #[serde(default)]
+    pub logical_date_offset: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NextAction {
+    pub action_type: String,
+    pub target_task_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+impl NextAction {
+    /// Evaluate the action condition against the result of the upstream task.
+    ///
+    /// Supported conditions:
+    /// - none / `success` - the upstream task succeeded (default)
+    /// - `failure` - the upstream task failed
+    /// - `always` - regardless of the outcome
+    /// - `<path> <op> <value>` - compare a field of the result, e.g. `data.rows_affected > 0`
+    pub fn is_satisfied_by(&self, result: &ExecutionResult) -> Result<bool, ExecutorError> {
+        let condition = match self.condition.as_deref().map(str::trim) {
+            None | Some("") => return Ok(result.success),
+            Some(condition) => condition,
+        };
+
+        match condition {
+            "success" | "on_success" => Ok(result.success),
+            "failure" | "on_failure" => Ok(!result.success),
+            "always" => Ok(true),
+            _ => evaluate_condition(condition, result),
+        }
+    }
+}
+
+// Task type - name of the executor in the ExecutorRegistry (case-insensitive)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct TaskType(String);
+
+impl From<&str> for TaskType {
+    fn from(name: &str) -> Self {
+        TaskType(name.trim().to_lowercase())
+    }
+}
+
+impl From<String> for TaskType {
+    fn from(name: String) -> Self {
+        TaskType::from(name.as_str())
+    }
+}
+
+impl From<TaskType> for String {
+    fn from(task_type: TaskType) -> Self {
+        task_type.0
+    }
+}
+
+impl FromStr for TaskType {
+    type Err = ExecutorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(ExecutorError::UnsupportedTaskType);
+        }
+        Ok(TaskType::from(s))
+    }
+}
+
+impl fmt::Display for TaskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TaskType {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Resolve the task type and metadata of a task stored in the task center
+    pub fn resolve_task(task: Task) -> Result<(TaskType, TaskMetadata), ExecutorError> {
+        let task_type = task.task_type.parse::<TaskType>()?;
+
+        let mut metadata = task.metadata;
+        if let Some(obj) = metadata.as_object_mut() {
+            obj.entry("task_id").or_insert_with(|| serde_json::Value::String(task.id.clone()));
+        }
+
+        let metadata: TaskMetadata = serde_json::from_value(metadata)
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid metadata of task {}: {}", task.id, e)))?;
+
+        Ok((task_type, metadata))
+    }
+}
+
+// Executor context containing all available resources
+#[derive(Clone)]
+pub struct ExecutorContext {
+    pub data_sources: Vec<data_source::Model>,
+    pub storages: Vec<storage::Model>,
+    // Metadata of registered data tables, looked up on demand
+    pub data_tables: Arc<DataTableService>,
+    // Shared connection pools of the data sources
+    pub pools: Arc<ConnectionPools>,
+    pub progress: ProgressReporter,
+    pub cancel: CancelHandle,
+    // Tables written by the run, whose cached query results are dropped afterwards
+    pub changes: TableChanges,
+}
+
+impl fmt::Debug for ExecutorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutorContext")
+            .field("data_sources", &self.data_sources)
+            .field("storages", &self.storages)
+            .field("progress", &self.progress)
+            .field("cancel", &self.cancel)
+            .finish_non_exhaustive()
+    }
+}
+
+// Reports the progress of a recorded run while it executes
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    run: Option<(String, Arc<TaskRunService>)>,
+}
+
+impl ProgressReporter {
+    pub fn report(&self, progress: serde_json::Value) {
+        if let Some((run_id, task_run_service)) = &self.run {
+            task_run_service.set_progress(run_id, progress);
+        }
+    }
+}
+
+// Tables written by a run, as (tenant, table) pairs
+#[derive(Clone, Debug, Default)]
+pub struct TableChanges {
+    tables: Arc<Mutex<HashSet<(String, String)>>>,
+}
+
+impl TableChanges {
+    /// Record a write to a table of a data source, before writing it
+    pub fn record(&self, data_source: &data_source::Model, table: &str) {
+        self.tables
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert((data_source.tenant_id.clone(), table.to_string()));
+    }
+
+    /// Written tables by tenant
+    fn take(&self) -> HashMap<String, Vec<String>> {
+        let tables = std::mem::take(
+            &mut *self.tables.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+        );
+        let mut by_tenant: HashMap<String, Vec<String>> = HashMap::new();
+        for (tenant_id, table) in tables {
+            by_tenant.entry(tenant_id).or_default().push(table);
+        }
+        by_tenant
+    }
+}
+
+impl fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("run_id", &self.run.as_ref().map(|(run_id, _)| run_id))
+            .finish()
+    }
+}
+
+// Execution result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionResult {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chain: Vec<ChainHop>,
+}
+
+impl ExecutionResult {
+    /// Result of a task that failed with an error
+    pub fn failed(e: &ExecutorError) -> Self {
+        Self {
+            success: false,
+            message: e.to_string(),
+            data: None,
+            chain: Vec::new(),
+        }
+    }
+}
+
+// A single hop of a next-action chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainHop {
+    pub depth: usize,
+    pub from_task_id: String,
+    pub to_task_id: String,
+    pub action_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    pub status: HopStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HopStatus {
+    Succeeded,
+    Failed,
+    ConditionNotMet,
+    AlreadyExecuted,
+    CycleDetected,
+    DependenciesNotSatisfied,
+}
+
+// Executor trait - all executors must implement this and be registered
+// in the ExecutorRegistry under their task type name
+#[async_trait]
+pub trait Executor: Send + Sync {
+    async fn execute(
+        &self,
+        metadata: TaskMetadata,
+        context: ExecutorContext,
+    ) -> Result<ExecutionResult, ExecutorError>;
+
+    /// Short description shown in the executor list
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// JSON schema of the task config
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object" })
+    }
+}
+
+// ExecutorEngine - main engine for task execution
+pub struct ExecutorEngine {
+    data_source_service: Arc<DataSourceService>,
+    storage_service: Arc<StorageService>,
+    data_table_service: Arc<DataTableService>,
+    connection_pools: Arc<ConnectionPools>,
+    query_cache: Arc<QueryCache>,
+    task_service: Arc<TaskService>,
+    task_run_service: Arc<TaskRunService>,
+    executor_registry: ExecutorRegistry,
+    // Bounds the number of submitted runs executing at once
+    workers: Arc<Semaphore>,
+    // Bounds the number of submitted runs waiting for a worker
+    queue: Arc<Semaphore>,
+    max_queued_runs: usize,
+    // Runs queued or executing on this instance, by run ID
+    active_runs: Mutex<HashMap<String, CancelHandle>>,
+}
+
+impl ExecutorEngine {
+    pub fn new(
+        data_source_service: Arc<DataSourceService>,
+        storage_service: Arc<StorageService>,
+        data_table_service: Arc<DataTableService>,
+        task_service: Arc<TaskService>,
+        task_run_service: Arc<TaskRunService>,
+        executor_registry: ExecutorRegistry,
+        config: ExecutorConfig,
+    ) -> Self {
+        Self {
+            connection_pools: data_source_service.connection_pools(),
+            query_cache: data_table_service.query_cache(),
+            data_source_service,
+            storage_service,
+            data_table_service,
+            task_service,
+            task_run_service,
+            executor_registry,
+            workers: Arc::new(Semaphore::new(config.max_concurrent_runs.max(1))),
+            queue: Arc::new(Semaphore::new(config.max_queued_runs)),
+            max_queued_runs: config.max_queued_runs,
+            active_runs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Execute a task based on its type and metadata
+    pub async fn execute_task(
+        &self,
+        task_type: TaskType,
+        metadata: TaskMetadata,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        self.execute_task_in_run(task_type, metadata, None).await
+    }
+
+    /// Queue a task for execution in the background and return its run immediately.
+    ///
+    /// The run starts as `queued` and is picked up once a worker is free; its status,
+    /// progress and result are available from the run history. Submissions are rejected
+    /// with `QueueFull` while `max_queued_runs` runs are already waiting.
+    pub async fn submit_task(
+        self: &Arc<Self>,
+        task_type: TaskType,
+        metadata: TaskMetadata,
+    ) -> Result<task_run::Model, ExecutorError> {
+        // Reject unknown task types before queueing
+        self.executor_registry.get(&task_type)?;
+        let slot = self.queue.clone().try_acquire_owned()
+            .map_err(|_| ExecutorError::QueueFull(self.max_queued_runs))?;
+
+        let run = self.create_run(&task_type, &metadata, TASK_RUN_STATUS_QUEUED).await
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to record run: {}", e)))?;
+        self.cancel_handle(&run.id);
+
+        let engine = self.clone();
+        let run_id = run.id.clone();
+        tokio::spawn(async move {
+            let _permit = match engine.workers.clone().acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => return,
+            };
+            drop(slot);
+
+            let task_id = metadata.task_id.clone();
+            if let Err(e) = engine.execute_task_in_run(task_type, metadata, Some(run_id.clone())).await {
+                log::warn!("Run {} of task {} failed: {}", run_id, task_id, e);
+            }
+        });
+
+        Ok(run)
+    }
+
+    /// Record a run of the task as skipped in the run history, without executing it
+    pub async fn skip_task(
+        &self,
+        task_type: &TaskType,
+        metadata: &TaskMetadata,
+        reason: &str,
+    ) -> Result<task_run::Model, sea_orm::DbErr> {
+        let run = self.create_run(task_type, metadata, TASK_RUN_STATUS_SKIPPED).await?;
+        self.task_run_service.skip(&run.id, reason).await
+    }
+
+    /// Cancel a run queued or executing on this instance.
+    ///
+    /// The executing tokio task is aborted, statements still running on PostgreSQL
+    /// are cancelled and the run is recorded as cancelled. Returns false when the
+    /// run is not active here.
+    pub async fn cancel_run(&self, run_id: &str) -> bool {
+        let cancel = match self.active_runs.lock().ok().and_then(|runs| runs.get(run_id).cloned()) {
+            Some(cancel) => cancel,
+            None => return false,
+        };
+
+        log::info!("Cancelling run {}", run_id);
+        cancel.cancel().await;
+
+        if let Err(e) = self.task_run_service.finish(run_id, &Err(ExecutorError::Cancelled)).await {
+            log::warn!("Failed to record cancellation of run {}: {}", run_id, e);
+        }
+        true
+    }
+
+    /// Cancel handle of an active run, registering the run if needed
+    fn cancel_handle(&self, run_id: &str) -> CancelHandle {
+        match self.active_runs.lock() {
+            Ok(mut runs) => runs.entry(run_id.to_string()).or_default().clone(),
+            Err(_) => CancelHandle::default(),
+        }
+    }
+
+    fn remove_active_run(&self, run_id: &str) {
+        if let Ok(mut runs) = self.active_runs.lock() {
+            runs.remove(run_id);
+        }
+    }
+
+    /// Execute a task and its next actions, recording the task under `run_id` if given
+    async fn execute_task_in_run(
+        &self,
+        task_type: TaskType,
+        metadata: TaskMetadata,
+        run_id: Option<String>,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Reject unknown task types before a run is recorded or reported
+        self.executor_registry.get(&task_type)?;
+
+        let mut result = match self.run_task(&task_type, &metadata, run_id).await {
+            Ok(result) => result,
+            // A rejected or cancelled task did not fail, its next actions are not followed
+            Err(e @ (ExecutorError::DependenciesNotSatisfied(_) | ExecutorError::Cancelled)) => return Err(e),
+            // A failed task still yields a result so that failure-conditioned actions are followed
+            Err(e) => ExecutionResult::failed(&e),
+        };
+
+        // Trigger next actions, recording every hop of the chain
+        let mut path = vec![metadata.task_id.clone()];
+        let mut executed = HashSet::from([metadata.task_id.clone()]);
+        let mut chain = Vec::new();
+        self.trigger_next_actions(&metadata, &result, &mut path, &mut executed, &mut chain)
+            .await;
+
+        result.chain = chain;
+        Ok(result)
+    }
+
+    /// Run a single task without triggering its next actions, recording it in the run history.
+    ///
+    /// A submitted run is passed in as `run_id`; otherwise a new run is recorded.
+    async fn run_task(
+        &self,
+        task_type: &TaskType,
+        metadata: &TaskMetadata,
+        run_id: Option<String>,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // A queued run may have been cancelled before a worker picked it up
+        let mut cancelled = false;
+        let run_id = match run_id {
+            Some(run_id) => {
+                if self.cancel_handle(&run_id).is_cancelled() {
+                    cancelled = true;
+                } else {
+                    match self.task_run_service.mark_running(&run_id).await {
+                        Ok(started) => cancelled = !started,
+                        Err(e) => log::warn!("Failed to record start of run {}: {}", run_id, e),
+                    }
+                }
+                Some(run_id)
+            }
+            None => self.start_run(task_type, metadata).await,
+        };
+
+        let progress = ProgressReporter {
+            run: run_id.clone().map(|run_id| (run_id, self.task_run_service.clone())),
+        };
+        let cancel = match &run_id {
+            Some(run_id) => self.cancel_handle(run_id),
+            None => CancelHandle::default(),
+        };
+
+        let result = if cancelled || cancel.is_cancelled() {
+            Err(ExecutorError::Cancelled)
+        } else {
+            self.execute_with_retry(task_type, metadata, progress, cancel).await
+        };
+
+        // Rejected and cancelled runs did not fail, so they are not reported to the task center
+        if !matches!(result, Err(ExecutorError::DependenciesNotSatisfied(_) | ExecutorError::Cancelled)) {
+            let status = match &result {
+                Ok(r) if r.success => TASK_STATUS_COMPLETED,
+                _ => TASK_STATUS_FAILED,
+            };
+            self.report_run(metadata, status).await;
+        }
+
+        if let Some(run_id) = run_id {
+            self.remove_active_run(&run_id);
+            if let Err(e) = self.task_run_service.finish(&run_id, &result).await {
+                log::warn!("Failed to record run {} of task {}: {}", run_id, metadata.task_id, e);
+            }
+        }
+
+        result
+    }
+
+    /// Insert a running entry into the run history (best effort)
+    async fn start_run(&self, task_type: &TaskType, metadata: &TaskMetadata) -> Option<String> {
+        match self.create_run(task_type, metadata, TASK_RUN_STATUS_RUNNING).await {
+            Ok(run) => Some(run.id),
+            Err(e) => {
+                log::warn!("Failed to record run of task {}: {}", metadata.task_id, e);
+                None
+            }
+        }
+    }
+
+    async fn create_run(
+        &self,
+        task_type: &TaskType,
+        metadata: &TaskMetadata,
+        status: &str,
+    ) -> Result<task_run::Model, sea_orm::DbErr> {
+        // Keep the raw config when it does not render, the run will fail on it anyway
+        let config = metadata.render_config().unwrap_or_else(|_| metadata.config.clone());
+
+        self.task_run_service
+            .create(
+                &metadata.task_id,
+                task_type.as_str(),
+                metadata.tenant_id.as_deref(),
+                metadata.logical_date.as_deref(),
+                config,
+                status,
+            )
+            .await
+    }
+
+    async fn execute_run(
+        &self,
+        task_type: &TaskType,
+        metadata: &TaskMetadata,
+        progress: ProgressReporter,
+        cancel: CancelHandle,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        // Get the executor registered for the task type
+        let executor = self.executor_registry.get(task_type)?;
+
+        // Refuse to run before all upstream tasks have completed
+        self.check_dependencies(metadata).await?;
+
+        // Build executor context with the tenant's data sources and storages
+        let context = self.build_context(metadata, progress, cancel.clone()).await?;
+        let changes = context.changes.clone();
+
+        // Execute the task on its own tokio task so that it can be aborted
+        let task_metadata = metadata.clone();
+        let handle = tokio::spawn(async move { executor.execute(task_metadata, context).await });
+        cancel.set_abort_handle(handle.abort_handle());
+
+        let result = match metadata.timeout_seconds() {
+            Some(seconds) => match tokio::time::timeout(Duration::from_secs(seconds), handle).await {
+                Ok(joined) => Self::join_result(joined),
+                Err(_) => {
+                    log::warn!("Task {} timed out after {} seconds", metadata.task_id, seconds);
+                    cancel.interrupt().await;
+                    Err(ExecutorError::Timeout(seconds))
+                }
+            },
+            None => Self::join_result(handle.await),
+        };
+        cancel.finish_attempt();
+
+        // Query results cached from the written tables are stale, whether or not the run succeeded
+        for (tenant_id, tables) in changes.take() {
+            self.query_cache.invalidate_tables(&tenant_id, &tables).await;
+        }
+
+        result
+    }
+
+    /// Check that every dependency has a successful run for the same logical date.
+    ///
+    /// Without a logical date the latest run of each dependency must have succeeded.
+    /// The run history is used rather than the task center, whose task status is only
+    /// that of the latest logical date run, so a backfill could not be checked against it.
+    async fn check_dependencies(&self, metadata: &TaskMetadata) -> Result<(), ExecutorError> {
+        let unsatisfied = self.task_run_service
+            .unsatisfied_dependencies(
+                &metadata.dependencies,
+                metadata.tenant_id.as_deref(),
+                metadata.logical_date.as_deref(),
+            )
+            .await;
+
+        if unsatisfied.is_empty() {
+            Ok(())
+        } else {
+            log::warn!(
+                "Task {} rejected, dependencies not satisfied: {}",
+                metadata.task_id,
+                unsatisfied.join(", ")
+            );
+            Err(ExecutorError::DependenciesNotSatisfied(unsatisfied))
+        }
+    }
+
+    /// Report the outcome of a run to the task center (best effort).
+    ///
+    /// A run for an older logical date than one already run, e.g. a backfill, is not
+    /// reported, so that the task status stays that of its latest logical date.
+    async fn report_run(&self, metadata: &TaskMetadata, status: &str) {
+        if self.task_run_service
+            .is_superseded(&metadata.task_id, metadata.tenant_id.as_deref(), metadata.logical_date.as_deref())
+            .await
+        {
+            return;
+        }
+
+        if let Err(e) = self.task_service.report_run(&metadata.task_id, status).await {
+            log::warn!("Failed to report run of task {}: {}", metadata.task_id, e);
+        }
+    }
+
+    fn join_result(
+        joined: Result<Result<ExecutionResult, ExecutorError>, tokio::task::JoinError>,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        match joined {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => Err(ExecutorError::Cancelled),
+            Err(e) => Err(ExecutorError::ExecutionFailed(format!("Executor panicked: {}", e))),
+        }
+    }
+
+    /// Build execution context with the data sources and storages of the run's tenant
+    ///
+    /// A run without a tenant gets none of them.
+    async fn build_context(
+        &self,
+        metadata: &TaskMetadata,
+        progress: ProgressReporter,
+        cancel: CancelHandle,
+    ) -> Result<ExecutorContext, ExecutorError> {
+        let (data_sources, storages) = match &metadata.tenant_id {
+            Some(tenant_id) => {
+                let data_sources = self.data_source_service
+                    .list_by_tenant(tenant_id)
+                    .await
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to fetch data sources: {}", e)))?;
+
+                let storages = self.storage_service
+                    .list_by_tenant(tenant_id)
+                    .await
+                    .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to fetch storages: {}", e)))?;
+
+                (data_sources, storages)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(ExecutorContext {
+            data_sources,
+            storages,
+            data_tables: self.data_table_service.clone(),
+            pools: self.connection_pools.clone(),
+            progress,
+            cancel,
+            changes: TableChanges::default(),
+        })
+    }
+
+    /// Executors available to the engine
+    pub fn executors(&self) -> Vec<ExecutorInfo> {
+        self.executor_registry.list()
+    }
+
+    /// Whether an executor is registered for the task type
+    pub fn supports(&self, task_type: &TaskType) -> bool {
+        self.executor_registry.contains(task_type)
+    }
+
+    /// Trigger next actions based on task metadata
+    ///
+    /// Each target task is fetched from the task center and executed, then its own
+    /// next actions are followed depth-first. `path` holds the tasks on the current
+    /// branch and is used to stop on cycles; `executed` makes sure a task runs at
+    /// most once per chain.
+    fn trigger_next_actions<'a>(
+        &'a self,
+        metadata: &'a TaskMetadata,
+        result: &'a ExecutionResult,
+        path: &'a mut Vec<String>,
+        executed: &'a mut HashSet<String>,
+        chain: &'a mut Vec<ChainHop>,
+    ) -> BoxFuture<'a, ()> {
+        async move {
+            for action in &metadata.next_actions {
+                let mut hop = ChainHop {
+                    depth: path.len(),
+                    from_task_id: metadata.task_id.clone(),
+                    to_task_id: action.target_task_id.clone(),
+                    action_type: action.action_type.clone(),
+                    condition: action.condition.clone(),
+                    status: HopStatus::Failed,
+                    message: String::new(),
+                };
+
+                match action.is_satisfied_by(result) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        hop.status = HopStatus::ConditionNotMet;
+                        hop.message = "Condition not met".to_string();
+                        chain.push(hop);
+                        continue;
+                    }
+                    Err(e) => {
+                        hop.message = e.to_string();
+                        chain.push(hop);
+                        continue;
+                    }
+                }
+
+                if !matches!(action.action_type.as_str(), "execute" | "trigger" | "execute_task") {
+                    hop.message = format!("Unsupported action type: {}", action.action_type);
+                    chain.push(hop);
+                    continue;
+                }
+
+                if path.contains(&action.target_task_id) {
+                    log::warn!(
+                        "Cycle detected: task {} -> {}",
+                        metadata.task_id,
+                        action.target_task_id
+                    );
+                    hop.status = HopStatus::CycleDetected;
+                    hop.message = format!("Cycle detected: {} -> {}", path.join(" -> "), action.target_task_id);
+                    chain.push(hop);
+                    continue;
+                }
+
+                if executed.contains(&action.target_task_id) {
+                    hop.status = HopStatus::AlreadyExecuted;
+                    hop.message = "Task already executed in this chain".to_string();
+                    chain.push(hop);
+                    continue;
+                }
+
+                log::info!(
+                    "Triggering next action: {} for task {}",
+                    action.action_type,
+                    action.target_task_id
+                );
+
+                // Fetch the target task from the task center
+                let target = match self.task_service.get(action.target_task_id.clone()).await {
+                    Ok(task) => TaskType::resolve_task(task),
+                    Err(e) => Err(ExecutorError::ExecutionFailed(format!("Failed to fetch task: {}", e))),
+                };
+                let (target_type, mut target_metadata) = match target {
+                    Ok(target) => target,
+                    Err(e) => {
+                        hop.message = e.to_string();
+                        chain.push(hop);
+                        continue;
+                    }
+                };
+
+                // Downstream tasks run for the same logical date, in the same tenant
+                if target_metadata.logical_date.is_none() {
+                    target_metadata.logical_date = metadata.logical_date.clone();
+                }
+                if target_metadata.tenant_id.is_none() {
+                    target_metadata.tenant_id = metadata.tenant_id.clone();
+                }
+
+                executed.insert(action.target_task_id.clone());
+
+                // A failed downstream task still yields a result so that
+                // failure-conditioned actions can be followed
+                let target_result = match self.run_task(&target_type, &target_metadata, None).await {
+                    Ok(target_result) => target_result,
+                    // Another branch of the chain may still complete the missing
+                    // dependencies and trigger this task again
+                    Err(e @ ExecutorError::DependenciesNotSatisfied(_)) => {
+                        executed.remove(&action.target_task_id);
+                        hop.status = HopStatus::DependenciesNotSatisfied;
+                        hop.message = e.to_string();
+                        chain.push(hop);
+                        continue;
+                    }
+                    Err(e) => ExecutionResult::failed(&e),
+                };
+
+                hop.status = if target_result.success { HopStatus::Succeeded } else { HopStatus::Failed };
+                hop.message = target_result.message.clone();
+                chain.push(hop);
+
+                path.push(action.target_task_id.clone());
+                self.trigger_next_actions(&target_metadata, &target_result, path, executed, chain)
+                    .await;
+                path.pop();
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Evaluate a `<path> <op> <value>` condition against an execution result
+fn evaluate_condition(condition: &str, result: &ExecutionResult) -> Result<bool, ExecutorError> {
+    use std::cmp::Ordering;
+    use serde_json::Value;
+
+    const OPERATORS: [&str; 6] = [">=", "<=", "==", "!=", ">", "<"];
+
+    let (pos, op) = OPERATORS.iter()
+        .filter_map(|op| condition.find(op).map(|pos| (pos, *op)))
+        .min_by_key(|(pos, op)| (*pos, std::cmp::Reverse(op.len())))
+        .ok_or_else(|| ExecutorError::ExecutionFailed(format!("Invalid condition: {}", condition)))?;
+
+    let field = condition[..pos].trim();
+    let literal = condition[pos + op.len()..].trim();
+    if field.is_empty() || literal.is_empty() {
+        return Err(ExecutorError::ExecutionFailed(format!("Invalid condition: {}", condition)));
+    }
+
+    let result_value = serde_json::to_value(result)
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to serialize result: {}", e)))?;
+    let actual = field.split('.')
+        .try_fold(&result_value, |value, key| value.get(key))
+        .unwrap_or(&Value::Null);
+
+    // Bare words are compared as strings
+    let expected: Value = serde_json::from_str(literal)
+        .unwrap_or_else(|_| Value::String(literal.trim_matches('\'').to_string()));
+
+    let ordering = match (actual, &expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (a, b) if a == b => Some(Ordering::Equal),
+        _ => None,
+    };
+
+    Ok(match op {
+        "==" => ordering == Some(Ordering::Equal),
+        "!=" => ordering != Some(Ordering::Equal),
+        ">" => ordering == Some(Ordering::Greater),
+        ">=" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        "<" => ordering == Some(Ordering::Less),
+        "<=" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        _ => unreachable!(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(success: bool, data: serde_json::Value) -> ExecutionResult {
+        ExecutionResult {
+            success,
+            message: String::new(),
+            data: Some(data),
+            chain: Vec::new(),
+        }
+    }
+
+    fn action(condition: Option<&str>) -> NextAction {
+        NextAction {
+            action_type: "execute".to_string(),
+            target_task_id: "next".to_string(),
+            condition: condition.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_keyword_conditions() {
+        let ok = result(true, serde_json::json!({}));
+        let failed = result(false, serde_json::json!({}));
+
+        assert!(action(None).is_satisfied_by(&ok).unwrap());
+        assert!(!action(None).is_satisfied_by(&failed).unwrap());
+        assert!(action(Some("failure")).is_satisfied_by(&failed).unwrap());
+        assert!(action(Some("always")).is_satisfied_by(&failed).unwrap());
+    }
+
+    #[test]
+    fn test_comparison_conditions() {
+        let ok = result(true, serde_json::json!({ "rows_affected": 3, "table": "dwd" }));
+
+        assert!(action(Some("data.rows_affected > 0")).is_satisfied_by(&ok).unwrap());
+        assert!(action(Some("data.rows_affected >= 3")).is_satisfied_by(&ok).unwrap());
+        assert!(!action(Some("data.rows_affected < 3")).is_satisfied_by(&ok).unwrap());
+        assert!(action(Some("data.table == 'dwd'")).is_satisfied_by(&ok).unwrap());
+        assert!(action(Some("success == true")).is_satisfied_by(&ok).unwrap());
+        assert!(!action(Some("data.missing == 1")).is_satisfied_by(&ok).unwrap());
+        assert!(action(Some("rows_affected")).is_satisfied_by(&ok).is_err());
+    }
+
+    // Executor of the `test` task type, with the outcome taken from `config.outcome`.
+    // `config.fail_on` makes the run for that logical date error, after `config.delay_ms`.
+    pub(super) struct TestExecutor;
+
+    #[async_trait]
+    impl Executor for TestExecutor {
+        async fn execute(
+            &self,
+            metadata: TaskMetadata,
+            _context: ExecutorContext,
+        ) -> Result<ExecutionResult, ExecutorError> {
+            if let Some(delay) = metadata.config.get("delay_ms").and_then(|v| v.as_u64()) {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            let fail_on = metadata.config.get("fail_on").and_then(|v| v.as_str());
+            if fail_on.is_some() && fail_on == metadata.logical_date.as_deref() {
+                return Err(ExecutorError::ExecutionFailed(format!("{} broke", metadata.task_id)));
+            }
+            match metadata.config.get("outcome").and_then(|v| v.as_str()) {
+                Some("error") => Err(ExecutorError::ExecutionFailed(format!("{} broke", metadata.task_id))),
+                Some("failed") => Ok(ExecutionResult {
+                    success: false,
+                    message: format!("{} failed", metadata.task_id),
+                    data: None,
+                    chain: Vec::new(),
+                }),
+                _ => Ok(ExecutionResult {
+                    success: true,
+                    message: format!("{} done for {}", metadata.task_id, metadata.logical_date.unwrap_or_default()),
+                    data: None,
+                    chain: Vec::new(),
+                }),
+            }
+        }
+    }
+
+    pub(super) fn task(id: &str, metadata: serde_json::Value) -> Task {
+        Task {
+            id: id.to_string(),
+            name: id.to_string(),
+            task_type: "test".to_string(),
+            status: "pending".to_string(),
+            metadata,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    pub(super) fn metadata(value: serde_json::Value) -> TaskMetadata {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// Engine running `test` tasks, with the given tasks served by a local task center.
+    ///
+    /// Databases answer with empty results, so the run history is not recorded.
+    pub(super) async fn engine(tasks: Vec<Task>, max_concurrent_runs: usize) -> Arc<ExecutorEngine> {
+        engine_with(tasks, ExecutorConfig { max_concurrent_runs, ..Default::default() }).await
+    }
+
+    pub(super) async fn engine_with(tasks: Vec<Task>, config: ExecutorConfig) -> Arc<ExecutorEngine> {
+        use std::collections::BTreeMap;
+        use axum::{extract::{Path, State}, http::StatusCode, Json};
+        use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase};
+        use crate::config::{CacheConfig, PoolConfig};
+        use crate::repository::*;
+        use super::super::task::TaskCenterClient;
+
+        let tasks: Arc<HashMap<String, Task>> = Arc::new(tasks.into_iter().map(|task| (task.id.clone(), task)).collect());
+        let task_center = axum::Router::new()
+            .route("/tasks/{id}", axum::routing::get(
+                |State(tasks): State<Arc<HashMap<String, Task>>>, Path(id): Path<String>| async move {
+                    tasks.get(&id).cloned().map(Json).ok_or(StatusCode::NOT_FOUND)
+                }
+            ))
+            .with_state(tasks);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, task_center).await });
+
+        let db = |results: usize| -> DatabaseConnection {
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results((0..results).map(|_| Vec::<BTreeMap<String, sea_orm::Value>>::new()))
+                .into_connection()
+        };
+        let pools = Arc::new(ConnectionPools::new(PoolConfig::default()));
+        let query_cache = Arc::new(QueryCache::new(&CacheConfig::default()));
+        let data_source_service = Arc::new(DataSourceService::new(
+            Arc::new(DataSourceRepository::new(db(100))),
+            pools.clone(),
+            query_cache.clone(),
+        ));
+        let data_table_service = Arc::new(DataTableService::new(
+            Arc::new(DataTableRepository::new(db(0))),
+            Arc::new(DataTableColumnRepository::new(db(0))),
+            Arc::new(DataTableUsageRepository::new(db(0))),
+            Arc::new(DataSourceRepository::new(db(0))),
+            pools,
+            query_cache,
+        ));
+
+        let mut registry = ExecutorRegistry::new();
+        registry.register("test", TestExecutor);
+
+        Arc::new(ExecutorEngine::new(
+            data_source_service,
+            Arc::new(StorageService::new(Arc::new(StorageRepository::new(db(100))))),
+            data_table_service,
+            Arc::new(TaskService::new(Arc::new(TaskCenterClient::new(base_url)))),
+            Arc::new(TaskRunService::new(Arc::new(TaskRunRepository::new(db(0))))),
+            registry,
+            config,
+        ))
+    }
+
+    fn hops(result: &ExecutionResult) -> Vec<(usize, &str, HopStatus)> {
+        result.chain.iter().map(|hop| (hop.depth, hop.to_task_id.as_str(), hop.status)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_chain_on_success() {
+        let engine = engine(vec![
+            task("dws_daily", serde_json::json!({
+                "config": {},
+                "next_actions": [{ "action_type": "execute", "target_task_id": "ads_report" }],
+            })),
+            task("ads_report", serde_json::json!({ "config": {} })),
+        ], 1).await;
+
+        let result = engine.execute_task(TaskType::from("test"), metadata(serde_json::json!({
+            "task_id": "dwd_sales",
+            "logical_date": "20251026",
+            "config": {},
+            "next_actions": [
+                { "action_type": "execute", "target_task_id": "dws_daily" },
+                { "action_type": "execute", "target_task_id": "alert", "condition": "failure" },
+            ],
+        }))).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(hops(&result), vec![
+            (1, "dws_daily", HopStatus::Succeeded),
+            (2, "ads_report", HopStatus::Succeeded),
+            (1, "alert", HopStatus::ConditionNotMet),
+        ]);
+        // Downstream tasks run for the logical date of the root
+        assert_eq!(result.chain[1].message, "ads_report done for 20251026");
+    }
+
+    #[tokio::test]
+    async fn test_chain_on_failure() {
+        let engine = engine(vec![
+            task("alert", serde_json::json!({ "config": {} })),
+            task("cleanup", serde_json::json!({ "config": {} })),
+        ], 1).await;
+
+        // The root task errors; failure and always actions still fire
+        let result = engine.execute_task(TaskType::from("test"), metadata(serde_json::json!({
+            "task_id": "dwd_sales",
+            "config": { "outcome": "error" },
+            "next_actions": [
+                { "action_type": "execute", "target_task_id": "dws_daily" },
+                { "action_type": "execute", "target_task_id": "alert", "condition": "failure" },
+                { "action_type": "execute", "target_task_id": "cleanup", "condition": "always" },
+            ],
+        }))).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.message.contains("dwd_sales broke"));
+        assert_eq!(hops(&result), vec![
+            (1, "dws_daily", HopStatus::ConditionNotMet),
+            (1, "alert", HopStatus::Succeeded),
+            (1, "cleanup", HopStatus::Succeeded),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_chain_stops_on_cycles() {
+        let engine = engine(vec![
+            task("b", serde_json::json!({
+                "config": {},
+                "next_actions": [
+                    { "action_type": "execute", "target_task_id": "c" },
+                    { "action_type": "execute", "target_task_id": "a" },
+                ],
+            })),
+            task("c", serde_json::json!({
+                "config": { "outcome": "failed" },
+                "next_actions": [{ "action_type": "execute", "target_task_id": "b", "condition": "always" }],
+            })),
+        ], 1).await;
+
+        let result = engine.execute_task(TaskType::from("test"), metadata(serde_json::json!({
+            "task_id": "a",
+            "config": {},
+            "next_actions": [{ "action_type": "execute", "target_task_id": "b" }],
+        }))).await.unwrap();
+
+        assert_eq!(hops(&result), vec![
+            (1, "b", HopStatus::Succeeded),
+            (2, "c", HopStatus::Failed),
+            (3, "b", HopStatus::CycleDetected),
+            (2, "a", HopStatus::CycleDetected),
+        ]);
+        assert_eq!(result.chain[2].message, "Cycle detected: a -> b -> c -> b");
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_when_queue_full() {
+        let engine = engine_with(Vec::new(), ExecutorConfig { max_concurrent_runs: 1, max_queued_runs: 2 }).await;
+        let submit = || engine.submit_task(TaskType::from("test"), metadata(serde_json::json!({
+            "task_id": "dwd_sales",
+            "config": {},
+        })));
+
+        let waiting = engine.queue.clone().try_acquire_many_owned(2).unwrap();
+        assert!(matches!(submit().await, Err(ExecutorError::QueueFull(2))));
+
+        // With a free slot the run is recorded, which fails against the empty mock database
+        // and gives the slot back
+        drop(waiting);
+        assert!(matches!(submit().await, Err(ExecutorError::ExecutionFailed(_))));
+        assert_eq!(engine.queue.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reject_unsupported_task_type() {
+        let engine = engine(Vec::new(), 1).await;
+        let metadata = || metadata(serde_json::json!({ "task_id": "dwd_sales", "config": {} }));
+
+        assert!(matches!(
+            engine.execute_task(TaskType::from("spark"), metadata()).await,
+            Err(ExecutorError::UnsupportedTaskType)
+        ));
+        assert!(matches!(
+            engine.submit_task(TaskType::from("spark"), metadata()).await,
+            Err(ExecutorError::UnsupportedTaskType)
+        ));
+    }
+}
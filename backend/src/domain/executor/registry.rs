@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::Serialize;
+use crate::domain::error::ExecutorError;
+use super::executors::{CopyExecutor, CsvExecutor, ExcelExecutor, ExportExecutor, HttpExecutor, QualityExecutor, SqlExecutor};
+use super::{Executor, TaskType};
+
+// Executor description returned by the executor list endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutorInfo {
+    pub name: String,
+    pub description: String,
+    pub config_schema: serde_json::Value,
+}
+
+// ExecutorRegistry - executors available to the engine, keyed by task type name
+#[derive(Clone, Default)]
+pub struct ExecutorRegistry {
+    executors: HashMap<String, Arc<dyn Executor>>,
+}
+
+impl ExecutorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the executors shipped with the backend
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register("sql", SqlExecutor);
+        registry.register("excel", ExcelExecutor);
+        registry.register("csv", CsvExecutor);
+        registry.register("copy", CopyExecutor);
+        registry.register("export", ExportExecutor);
+        registry.register("http", HttpExecutor);
+        registry.register("quality", QualityExecutor);
+        registry
+    }
+
+    /// Register an executor under a task type name, replacing any previous one
+    pub fn register(&mut self, name: &str, executor: impl Executor + 'static) {
+        let name = TaskType::from(name).as_str().to_string();
+        if self.executors.insert(name.clone(), Arc::new(executor)).is_some() {
+            log::warn!("Executor '{}' registered twice, the last registration wins", name);
+        }
+    }
+
+    pub fn get(&self, task_type: &TaskType) -> Result<Arc<dyn Executor>, ExecutorError> {
+        self.executors
+            .get(task_type.as_str())
+            .cloned()
+            .ok_or(ExecutorError::UnsupportedTaskType)
+    }
+
+    pub fn contains(&self, task_type: &TaskType) -> bool {
+        self.executors.contains_key(task_type.as_str())
+    }
+
+    /// All registered executors, sorted by name
+    pub fn list(&self) -> Vec<ExecutorInfo> {
+        let mut executors: Vec<ExecutorInfo> = self.executors
+            .iter()
+            .map(|(name, executor)| {
+                let mut config_schema = executor.config_schema();
+                // Options handled by the engine for every task type
+                if let Some(properties) = config_schema
+                    .get_mut("properties")
+                    .and_then(|p| p.as_object_mut())
+                {
+                    properties.entry("timeout_seconds").or_insert_with(|| serde_json::json!({
+                        "type": "integer",
+                        "description": "Abort the run after this many seconds"
+                    }));
+                }
+
+                ExecutorInfo {
+                    name: name.clone(),
+                    description: executor.description().to_string(),
+                    config_schema,
+                }
+            })
+            .collect();
+
+        executors.sort_by(|a, b| a.name.cmp(&b.name));
+        executors
+    }
+}
@@ -0,0 +1,246 @@
+use std::time::Duration;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use crate::domain::error::ExecutorError;
+use super::{CancelHandle, ExecutionResult, ExecutorEngine, ProgressReporter, TaskMetadata, TaskType};
+
+// Longest delay between attempts, whatever the policy says
+const MAX_BACKOFF_SECONDS: u64 = 86_400;
+
+// Retry policy of a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    // Total number of attempts, including the first one
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    // Delay before the first retry
+    #[serde(default = "default_backoff_seconds")]
+    pub backoff_seconds: u64,
+    // Factor applied to the delay after every retry
+    #[serde(default = "default_backoff_multiplier")]
+    pub backoff_multiplier: f64,
+    // Upper bound of the delay, itself capped at MAX_BACKOFF_SECONDS
+    #[serde(default = "default_max_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+    // Error kinds worth retrying, see `ExecutorError::kind`. A failed result
+    // (e.g. a failing SQL statement) counts as `execution_failed`.
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<String>,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_backoff_seconds() -> u64 {
+    10
+}
+
+fn default_backoff_multiplier() -> f64 {
+    2.0
+}
+
+fn default_max_backoff_seconds() -> u64 {
+    600
+}
+
+fn default_retry_on() -> Vec<String> {
+    vec![
+        "connection_failed".to_string(),
+        "download_failed".to_string(),
+        "upload_failed".to_string(),
+    ]
+}
+
+impl RetryPolicy {
+    /// Whether an attempt that failed with the given kind should be retried
+    pub fn is_retryable(&self, kind: &str) -> bool {
+        // Cancelled runs and unknown task types never succeed on retry
+        if matches!(kind, "cancelled" | "unsupported_task_type") {
+            return false;
+        }
+        self.retry_on.iter().any(|k| k == kind)
+    }
+
+    /// Delay before the given retry (1 = first retry)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.backoff_multiplier.max(1.0).powi(retry.saturating_sub(1) as i32);
+        let max_seconds = self.max_backoff_seconds.min(MAX_BACKOFF_SECONDS) as f64;
+        // An overflowing (infinite or NaN) delay ends up at the maximum
+        let seconds = (self.backoff_seconds as f64 * factor).min(max_seconds);
+        Duration::from_secs_f64(seconds.max(0.0))
+    }
+}
+
+// Outcome of a single attempt, recorded under `attempts` in the result data
+#[derive(Debug, Clone, Serialize)]
+pub struct AttemptRecord {
+    pub attempt: u32,
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<&'static str>,
+    pub message: String,
+}
+
+impl ExecutorEngine {
+    /// Execute a task, retrying failed attempts according to its retry policy.
+    ///
+    /// With a policy, every attempt is recorded under `attempts` in the result data;
+    /// an error that was retried is turned into a failed result so that the
+    /// attempts are not lost.
+    pub(super) async fn execute_with_retry(
+        &self,
+        task_type: &TaskType,
+        metadata: &TaskMetadata,
+        progress: ProgressReporter,
+        cancel: CancelHandle,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        let policy = match &metadata.retry {
+            Some(policy) => policy,
+            None => return self.execute_run(task_type, metadata, progress, cancel).await,
+        };
+
+        let max_attempts = policy.max_attempts.max(1);
+        let mut attempts = Vec::new();
+
+        loop {
+            let attempt = attempts.len() as u32 + 1;
+            let started_at = Utc::now().to_rfc3339();
+            let result = self.execute_run(task_type, metadata, progress.clone(), cancel.clone()).await;
+
+            let error_kind = match &result {
+                Ok(r) if r.success => None,
+                Ok(_) => Some("execution_failed"),
+                Err(e) => Some(e.kind()),
+            };
+            attempts.push(AttemptRecord {
+                attempt,
+                started_at,
+                finished_at: Utc::now().to_rfc3339(),
+                success: error_kind.is_none(),
+                error_kind,
+                message: match &result {
+                    Ok(r) => r.message.clone(),
+                    Err(e) => e.to_string(),
+                },
+            });
+
+            let retry = match error_kind {
+                Some(kind) => attempt < max_attempts && policy.is_retryable(kind) && !cancel.is_cancelled(),
+                None => false,
+            };
+            if !retry {
+                return Self::with_attempts(result, attempts);
+            }
+
+            let delay = policy.backoff(attempt);
+            log::warn!(
+                "Attempt {} of {} for task {} failed ({}), retrying in {:?}",
+                attempt, max_attempts, metadata.task_id, error_kind.unwrap_or_default(), delay
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel.cancelled() => {}
+            }
+
+            if cancel.is_cancelled() {
+                return Err(ExecutorError::Cancelled);
+            }
+        }
+    }
+
+    fn with_attempts(
+        result: Result<ExecutionResult, ExecutorError>,
+        attempts: Vec<AttemptRecord>,
+    ) -> Result<ExecutionResult, ExecutorError> {
+        let mut result = match result {
+            Ok(result) => result,
+            // Keep errors as they are when nothing was retried
+            Err(e) if attempts.len() == 1 => return Err(e),
+            Err(e @ ExecutorError::Cancelled) => return Err(e),
+            Err(e) => ExecutionResult {
+                success: false,
+                message: format!("Task failed after {} attempts: {}", attempts.len(), e),
+                data: None,
+                chain: Vec::new(),
+            },
+        };
+
+        let attempts = serde_json::to_value(&attempts).unwrap_or_default();
+        match result.data.as_mut().and_then(|data| data.as_object_mut()) {
+            Some(data) => {
+                data.insert("attempts".to_string(), attempts);
+            }
+            None => {
+                let previous = result.data.take();
+                let mut data = serde_json::json!({ "attempts": attempts });
+                if let Some(previous) = previous {
+                    data["result"] = previous;
+                }
+                result.data = Some(data);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy() {
+        let policy: RetryPolicy = serde_json::from_value(serde_json::json!({
+            "max_attempts": 5,
+            "backoff_seconds": 10,
+            "max_backoff_seconds": 30,
+        })).unwrap();
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(10));
+        assert_eq!(policy.backoff(2), Duration::from_secs(20));
+        assert_eq!(policy.backoff(3), Duration::from_secs(30));
+
+        assert!(policy.is_retryable(ExecutorError::ConnectionFailed.kind()));
+        assert!(policy.is_retryable(ExecutorError::DownloadFailed("503".to_string()).kind()));
+        assert!(!policy.is_retryable(ExecutorError::ExecutionFailed("syntax".to_string()).kind()));
+        assert!(!policy.is_retryable(ExecutorError::Cancelled.kind()));
+
+        // Delays are capped, even when the policy overflows them
+        let policy: RetryPolicy = serde_json::from_value(serde_json::json!({
+            "backoff_seconds": u64::MAX,
+            "backoff_multiplier": 1e300,
+            "max_backoff_seconds": u64::MAX,
+        })).unwrap();
+        assert_eq!(policy.backoff(3), Duration::from_secs(MAX_BACKOFF_SECONDS));
+        let policy = RetryPolicy { backoff_seconds: 0, ..policy };
+        assert_eq!(policy.backoff(3), Duration::from_secs(MAX_BACKOFF_SECONDS));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_during_backoff() {
+        use super::super::tests::{engine, metadata};
+
+        let engine = engine(Vec::new(), 1).await;
+        let metadata = metadata(serde_json::json!({
+            "task_id": "dwd_sales",
+            "config": { "outcome": "error" },
+            "retry": { "backoff_seconds": 3600, "retry_on": ["execution_failed"] },
+        }));
+        let cancel = CancelHandle::default();
+
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel().await;
+        });
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            engine.execute_with_retry(&TaskType::from("test"), &metadata, ProgressReporter::default(), cancel),
+        ).await;
+        assert!(matches!(result, Ok(Err(ExecutorError::Cancelled))));
+    }
+}
@@ -0,0 +1,233 @@
+// SQL script splitting
+//
+// Splits a script into individual statements on `;`, ignoring semicolons inside
+// string literals, quoted identifiers, dollar-quoted bodies and comments.
+
+use std::sync::OnceLock;
+use regex::Regex;
+
+/// Split a SQL script into statements.
+///
+/// Handles `'...'` (including `E'...'` escapes), `"..."`, `` `...` ``,
+/// `$$...$$` / `$tag$...$tag$`, `-- ...` and nested `/* ... */`.
+/// Statements consisting only of whitespace and comments are dropped.
+pub fn split_statements(script: &str) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut has_content = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            // Line comment
+            '-' if next == Some('-') => {
+                let end = find_from(&chars, i, |ch| ch == '\n').unwrap_or(chars.len());
+                current.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
+            // Block comment, nestable in PostgreSQL
+            '/' if next == Some('*') => {
+                let mut depth = 0;
+                let mut j = i;
+                while j < chars.len() {
+                    if chars[j] == '/' && chars.get(j + 1) == Some(&'*') {
+                        depth += 1;
+                        j += 2;
+                    } else if chars[j] == '*' && chars.get(j + 1) == Some(&'/') {
+                        depth -= 1;
+                        j += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        j += 1;
+                    }
+                }
+                let end = j.min(chars.len());
+                current.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
+            '\'' => {
+                let escapes = i > 0
+                    && matches!(chars[i - 1], 'e' | 'E')
+                    && (i < 2 || !is_identifier_char(chars[i - 2]));
+                let end = end_of_quoted(&chars, i, '\'', escapes);
+                current.extend(&chars[i..end]);
+                has_content = true;
+                i = end;
+                continue;
+            }
+            '"' | '`' => {
+                let end = end_of_quoted(&chars, i, c, false);
+                current.extend(&chars[i..end]);
+                has_content = true;
+                i = end;
+                continue;
+            }
+            '$' if i == 0 || !is_identifier_char(chars[i - 1]) => {
+                if let Some(tag_end) = dollar_tag_end(&chars, i) {
+                    let tag: String = chars[i..=tag_end].iter().collect();
+                    let body_start = tag_end + 1;
+                    let end = find_tag(&chars, body_start, &tag)
+                        .map(|pos| pos + tag.chars().count())
+                        .unwrap_or(chars.len());
+                    current.extend(&chars[i..end]);
+                    has_content = true;
+                    i = end;
+                    continue;
+                }
+            }
+            ';' => {
+                if has_content {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+                has_content = false;
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        if !c.is_whitespace() {
+            has_content = true;
+        }
+        current.push(c);
+        i += 1;
+    }
+
+    if has_content {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn find_from(chars: &[char], start: usize, pred: impl Fn(char) -> bool) -> Option<usize> {
+    chars[start..].iter().position(|&c| pred(c)).map(|pos| start + pos)
+}
+
+/// Index just past the closing quote of a quoted section starting at `start`.
+/// A doubled quote is an escaped quote; backslash escapes only apply to E-strings.
+fn end_of_quoted(chars: &[char], start: usize, quote: char, backslash_escapes: bool) -> usize {
+    let mut j = start + 1;
+    while j < chars.len() {
+        if backslash_escapes && chars[j] == '\\' {
+            j += 2;
+            continue;
+        }
+        if chars[j] == quote {
+            if chars.get(j + 1) == Some(&quote) {
+                j += 2;
+                continue;
+            }
+            return j + 1;
+        }
+        j += 1;
+    }
+    chars.len()
+}
+
+/// Table written by a statement, as named in it (possibly schema-qualified and quoted).
+///
+/// Recognizes INSERT, UPDATE, DELETE, TRUNCATE, MERGE, REPLACE and CREATE, DROP or
+/// ALTER TABLE (and materialized view refreshes). Statements starting with `WITH` or
+/// calling functions are not recognized.
+pub fn written_table(statement: &str) -> Option<String> {
+    static WRITE: OnceLock<Regex> = OnceLock::new();
+    let regex = WRITE.get_or_init(|| Regex::new(
+        r#"(?is)^(?:\s+|--[^\n]*\n?|/\*.*?\*/)*(?:insert(?:\s+ignore)?\s+into|replace\s+into|update(?:\s+only)?|delete\s+from(?:\s+only)?|truncate(?:\s+table)?(?:\s+only)?|merge\s+into|create\s+(?:or\s+replace\s+)?(?:(?:temporary|temp|unlogged)\s+)?(?:table|materialized\s+view)(?:\s+if\s+not\s+exists)?|drop\s+(?:table|materialized\s+view)(?:\s+if\s+exists)?|alter\s+table(?:\s+if\s+exists)?(?:\s+only)?|refresh\s+materialized\s+view(?:\s+concurrently)?)\s+((?:[\w$]+|"[^"]+"|`[^`]+`)(?:\.(?:[\w$]+|"[^"]+"|`[^`]+`))*)"#
+    ).expect("valid regex"));
+
+    regex.captures(statement).map(|captures| captures[1].to_string())
+}
+
+/// If a dollar-quote tag (`$$` or `$tag$`) starts at `start`, return the index of its closing `$`
+fn dollar_tag_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut j = start + 1;
+    while j < chars.len() {
+        let c = chars[j];
+        if c == '$' {
+            return Some(j);
+        }
+        let valid = if j == start + 1 {
+            c.is_alphabetic() || c == '_'
+        } else {
+            c.is_alphanumeric() || c == '_'
+        };
+        if !valid {
+            return None;
+        }
+        j += 1;
+    }
+    None
+}
+
+fn find_tag(chars: &[char], start: usize, tag: &str) -> Option<usize> {
+    let tag: Vec<char> = tag.chars().collect();
+    (start..chars.len()).find(|&j| chars[j..].starts_with(&tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_simple_script() {
+        let statements = split_statements(
+            "CREATE TABLE t (id INT);\n\nCOMMENT ON TABLE t IS 'a; b';\n-- trailing comment\n",
+        );
+
+        assert_eq!(statements, vec![
+            "CREATE TABLE t (id INT)",
+            "COMMENT ON TABLE t IS 'a; b'",
+        ]);
+    }
+
+    #[test]
+    fn test_split_quotes_and_comments() {
+        let statements = split_statements(
+            "SELECT 'it''s;' AS \"a;b\"; /* x; /* nested; */ y; */ SELECT E'\\';' ; -- c;\nSELECT 1",
+        );
+
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "SELECT 'it''s;' AS \"a;b\"");
+        assert!(statements[1].ends_with("SELECT E'\\';'"));
+        assert!(statements[2].ends_with("SELECT 1"));
+    }
+
+    #[test]
+    fn test_split_dollar_quoting() {
+        let script = "CREATE FUNCTION f() RETURNS TRIGGER AS $$\nBEGIN\n  NEW.x = 1;\n  RETURN NEW;\nEND;\n$$ LANGUAGE plpgsql;\n\
+                      DO $body$ BEGIN PERFORM 1; END $body$;\nSELECT $1;";
+        let statements = split_statements(script);
+
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].ends_with("$$ LANGUAGE plpgsql"));
+        assert_eq!(statements[1], "DO $body$ BEGIN PERFORM 1; END $body$");
+        assert_eq!(statements[2], "SELECT $1");
+    }
+
+    #[test]
+    fn test_written_table() {
+        assert_eq!(written_table("INSERT INTO dw.sales (id) SELECT 1").as_deref(), Some("dw.sales"));
+        assert_eq!(
+            written_table("-- reload\n/* daily */ TRUNCATE TABLE \"Sales Daily\"").as_deref(),
+            Some("\"Sales Daily\"")
+        );
+        assert_eq!(written_table("create table if not exists `orders`(id int)").as_deref(), Some("`orders`"));
+        assert_eq!(written_table("DELETE FROM sales WHERE dt = '20250101'").as_deref(), Some("sales"));
+        assert_eq!(written_table("SELECT * FROM sales"), None);
+        assert_eq!(written_table("SET search_path TO dw"), None);
+    }
+}
@@ -0,0 +1,201 @@
+// Task config templates
+//
+// String values in a task config may reference `${...}` variables which are
+// rendered against the logical date of the run and user-supplied params.
+
+use std::collections::HashMap;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use regex::{Captures, Regex};
+use serde_json::Value;
+use crate::domain::error::ExecutorError;
+
+const DATE_FORMAT: &str = "%Y%m%d";
+
+/// Variables available to a template
+pub struct TemplateVars<'a> {
+    pub logical_date: NaiveDate,
+    pub params: &'a HashMap<String, Value>,
+}
+
+impl<'a> TemplateVars<'a> {
+    /// Build template variables, defaulting the logical date to today
+    pub fn new(
+        logical_date: Option<&str>,
+        params: &'a HashMap<String, Value>,
+    ) -> Result<Self, ExecutorError> {
+        let logical_date = match logical_date {
+            Some(date) => parse_logical_date(date)?,
+            None => Local::now().date_naive(),
+        };
+
+        Ok(Self { logical_date, params })
+    }
+
+    /// Resolve a single variable expression.
+    ///
+    /// Supported expressions:
+    /// - any key of `params` (takes precedence over built-ins)
+    /// - `dt`, `dt-N`, `dt+N` - logical date shifted by N days
+    /// - `week_start`, `month_start`, `month_end`, `year_start`
+    ///
+    /// Dates render as yyyyMMdd unless a chrono format is given, e.g. `${dt-1:%Y-%m-%d}`.
+    fn resolve(&self, expr: &str) -> Result<String, ExecutorError> {
+        let (name, format) = match expr.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format)),
+            None => (expr.trim(), None),
+        };
+
+        if let Some(value) = self.params.get(name) {
+            return Ok(match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+        }
+
+        let date = self.resolve_date(name)?
+            .ok_or_else(|| ExecutorError::ExecutionFailed(format!("Unknown template variable: {}", name)))?;
+
+        format_date(date, format.unwrap_or(DATE_FORMAT))
+    }
+
+    /// Date of a built-in variable, `None` for unknown names
+    fn resolve_date(&self, name: &str) -> Result<Option<NaiveDate>, ExecutorError> {
+        let date = self.logical_date;
+
+        Ok(match name {
+            "dt" => Some(date),
+            "week_start" => Some(date - Duration::days(date.weekday().num_days_from_monday() as i64)),
+            "month_start" => date.with_day(1),
+            "month_end" => {
+                let next_month = if date.month() == 12 {
+                    NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)
+                } else {
+                    NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1)
+                };
+                next_month.map(|d| d - Duration::days(1))
+            }
+            "year_start" => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+            _ => {
+                let offset = match name.strip_prefix("dt") {
+                    Some(offset) => offset.replace(' ', ""),
+                    None => return Ok(None),
+                };
+                let days: i64 = match offset.strip_prefix('+').unwrap_or(&offset).parse() {
+                    Ok(days) => days,
+                    Err(_) => return Ok(None),
+                };
+                let date = Duration::try_days(days)
+                    .and_then(|offset| date.checked_add_signed(offset))
+                    .ok_or_else(|| ExecutorError::ExecutionFailed("Date offset out of range".to_string()))?;
+                Some(date)
+            }
+        })
+    }
+}
+
+/// Format a date; a time or offset specifier (e.g. `%H`, `%z`) has nothing to format
+fn format_date(date: NaiveDate, format: &str) -> Result<String, ExecutorError> {
+    use std::fmt::Write;
+    use chrono::format::StrftimeItems;
+
+    let invalid = || ExecutorError::ExecutionFailed(format!("Invalid date format: {}", format));
+    let items = StrftimeItems::new(format).parse().map_err(|_| invalid())?;
+
+    let mut text = String::new();
+    write!(text, "{}", date.format_with_items(items.iter())).map_err(|_| invalid())?;
+    Ok(text)
+}
+
+/// Parse a logical date in yyyyMMdd or yyyy-MM-dd format
+pub fn parse_logical_date(date: &str) -> Result<NaiveDate, ExecutorError> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+        .map_err(|_| ExecutorError::ExecutionFailed(
+            format!("Invalid logical date '{}', expected yyyyMMdd", date)
+        ))
+}
+
+/// Render all `${...}` variables in a string
+pub fn render(template: &str, vars: &TemplateVars) -> Result<String, ExecutorError> {
+    let regex = Regex::new(r"\$\{([^}]+)\}")
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid regex: {}", e)))?;
+
+    let mut error = None;
+    let rendered = regex.replace_all(template, |caps: &Captures| {
+        match vars.resolve(&caps[1]) {
+            Ok(value) => value,
+            Err(e) => {
+                error.get_or_insert(e);
+                String::new()
+            }
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(rendered.into_owned()),
+    }
+}
+
+/// Render every string in a JSON value, recursively
+pub fn render_value(value: &Value, vars: &TemplateVars) -> Result<Value, ExecutorError> {
+    Ok(match value {
+        Value::String(s) => Value::String(render(s, vars)?),
+        Value::Array(items) => Value::Array(
+            items.iter()
+                .map(|item| render_value(item, vars))
+                .collect::<Result<_, _>>()?
+        ),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(k, v)| Ok((k.clone(), render_value(v, vars)?)))
+                .collect::<Result<_, ExecutorError>>()?
+        ),
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_date_variables() {
+        let params = HashMap::new();
+        let vars = TemplateVars::new(Some("20251026"), &params).unwrap();
+
+        assert_eq!(render("dt = '${dt}'", &vars).unwrap(), "dt = '20251026'");
+        assert_eq!(render("${dt-1} ${dt+7}", &vars).unwrap(), "20251025 20251102");
+        assert_eq!(render("${month_start}..${month_end}", &vars).unwrap(), "20251001..20251031");
+        assert_eq!(render("${week_start} ${year_start}", &vars).unwrap(), "20251020 20250101");
+        assert_eq!(render("${dt-26:%Y-%m-%d}", &vars).unwrap(), "2025-09-30");
+        assert!(render("${unknown}", &vars).is_err());
+        assert!(render("${dt:%Y-%m-%d %H}", &vars).is_err());
+        assert!(render("${dt:%z}", &vars).is_err());
+        assert!(render("${dt:%Q}", &vars).is_err());
+
+        // Offsets beyond the supported dates are rejected instead of panicking
+        assert!(render("${dt+100000000}", &vars).is_err());
+        assert!(render("${dt-9223372036854775807}", &vars).is_err());
+    }
+
+    #[test]
+    fn test_render_params_and_values() {
+        let params = HashMap::from([
+            ("shop".to_string(), Value::String("A".to_string())),
+            ("limit".to_string(), Value::from(10)),
+        ]);
+        let vars = TemplateVars::new(Some("2025-12-31"), &params).unwrap();
+
+        let config = serde_json::json!({
+            "sql": "SELECT * FROM t WHERE shop = '${shop}' AND dt = '${dt}' LIMIT ${limit}",
+            "file_path": ["exports/${month_end}.xlsx"],
+            "batch_size": 100,
+        });
+        let rendered = render_value(&config, &vars).unwrap();
+
+        assert_eq!(rendered["sql"], "SELECT * FROM t WHERE shop = 'A' AND dt = '20251231' LIMIT 10");
+        assert_eq!(rendered["file_path"][0], "exports/20251231.xlsx");
+        assert_eq!(rendered["batch_size"], 100);
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use super::data_source::DataSourceService;
+use super::error::{AppError, ServiceError};
+use super::executor::{
+    BackfillOptions, BackfillResult, ExecutionResult, ExecutorEngine, NextAction, RetryPolicy,
+    TaskMetadata, TaskType,
+};
+
+// Job definition file, e.g. jobs/dwd_rival_stats_di_1d.yaml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDefinition {
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub job_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub next_actions: Vec<NextAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillJobRequest {
+    #[serde(flatten)]
+    pub run: RunJobRequest,
+    #[serde(flatten)]
+    pub options: BackfillOptions,
+}
+
+// Runs belong to the tenant of the caller, which also scopes the data sources jobs refer to
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RunJobRequest {
+    #[serde(default)]
+    pub logical_date: Option<String>,
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+// JobService - loads job definitions from the jobs directory and runs them
+pub struct JobService {
+    jobs_dir: PathBuf,
+    data_source_service: Arc<DataSourceService>,
+    executor_engine: Arc<ExecutorEngine>,
+}
+
+impl JobService {
+    pub fn new(
+        jobs_dir: impl Into<PathBuf>,
+        data_source_service: Arc<DataSourceService>,
+        executor_engine: Arc<ExecutorEngine>,
+    ) -> Self {
+        Self {
+            jobs_dir: jobs_dir.into(),
+            data_source_service,
+            executor_engine,
+        }
+    }
+
+    /// List all job definitions, sorted by name
+    pub async fn list(&self) -> Result<Vec<JobDefinition>, ServiceError> {
+        let mut entries = tokio::fs::read_dir(&self.jobs_dir).await
+            .map_err(|e| ServiceError::InvalidInput(format!("Failed to read jobs directory: {}", e)))?;
+
+        let mut jobs = Vec::new();
+        while let Some(entry) = entries.next_entry().await
+            .map_err(|e| ServiceError::InvalidInput(format!("Failed to read jobs directory: {}", e)))?
+        {
+            let path = entry.path();
+            let is_yaml = matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml"));
+            if !is_yaml {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                jobs.push(self.get(name).await?);
+            }
+        }
+
+        jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(jobs)
+    }
+
+    /// Load a job definition by name (the file name without extension)
+    pub async fn get(&self, name: &str) -> Result<JobDefinition, ServiceError> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(ServiceError::InvalidInput(format!("Invalid job name: {}", name)));
+        }
+
+        let mut content = None;
+        for extension in ["yaml", "yml"] {
+            let path = self.jobs_dir.join(format!("{}.{}", name, extension));
+            if let Ok(text) = tokio::fs::read_to_string(&path).await {
+                content = Some(text);
+                break;
+            }
+        }
+        let content = content.ok_or(ServiceError::NotFound)?;
+
+        let mut job = Self::parse(&content)
+            .map_err(|e| ServiceError::InvalidInput(format!("Invalid job {}: {}", name, e)))?;
+        job.name = name.to_string();
+        Ok(job)
+    }
+
+    /// Parse a job definition from YAML
+    pub fn parse(content: &str) -> Result<JobDefinition, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// Resolve a job into the task type and metadata expected by the executor engine.
+    ///
+    /// `config.source` names a data source of the tenant and is resolved to `config.data_source_id`.
+    pub async fn resolve(
+        &self,
+        job: &JobDefinition,
+        tenant_id: &str,
+        req: &RunJobRequest,
+    ) -> Result<(TaskType, TaskMetadata), ServiceError> {
+        let task_type = job.job_type.parse::<TaskType>()
+            .ok()
+            .filter(|task_type| self.executor_engine.supports(task_type))
+            .ok_or_else(|| ServiceError::InvalidInput(format!("Unsupported job type: {}", job.job_type)))?;
+
+        validate_params(&req.params)?;
+
+        let mut config = job.config.clone();
+        let obj = config.as_object_mut()
+            .ok_or_else(|| ServiceError::InvalidInput("Job config must be a mapping".to_string()))?;
+
+        if let Some(source) = obj.get("source").and_then(|v| v.as_str()).map(str::to_string) {
+            let data_source_id = self.resolve_data_source(&source, tenant_id).await?;
+            obj.insert("data_source_id".to_string(), serde_json::Value::String(data_source_id));
+        }
+
+        let metadata = TaskMetadata {
+            task_id: job.task_id.clone().unwrap_or_else(|| job.name.clone()),
+            tenant_id: Some(tenant_id.to_string()),
+            logical_date: req.logical_date.clone(),
+            dependencies: job.dependencies.clone(),
+            next_actions: job.next_actions.clone(),
+            config,
+            params: req.params.clone(),
+            schedule: None,
+            retry: job.retry.clone(),
+        };
+
+        Ok((task_type, metadata))
+    }
+
+    /// Run a job by name through the executor engine, for a tenant
+    pub async fn run(&self, name: &str, tenant_id: &str, req: RunJobRequest) -> Result<ExecutionResult, AppError> {
+        let job = self.get(name).await?;
+        let (task_type, metadata) = self.resolve(&job, tenant_id, &req).await?;
+
+        log::info!("Running job {} as task {}", name, metadata.task_id);
+        Ok(self.executor_engine.execute_task(task_type, metadata).await?)
+    }
+
+    /// Backfill a job by name over a range of logical dates, for a tenant
+    pub async fn backfill(&self, name: &str, tenant_id: &str, req: BackfillJobRequest) -> Result<BackfillResult, AppError> {
+        let job = self.get(name).await?;
+        let (task_type, metadata) = self.resolve(&job, tenant_id, &req.run).await?;
+
+        Ok(self.executor_engine.backfill(task_type, metadata, req.options).await?)
+    }
+
+    /// Find the ID of a data source of the tenant by name
+    async fn resolve_data_source(&self, source: &str, tenant_id: &str) -> Result<String, ServiceError> {
+        let data_sources = self.data_source_service.list_by_tenant(tenant_id).await?;
+
+        let matches: Vec<_> = data_sources.iter()
+            .filter(|ds| ds.name == source)
+            .collect();
+
+        match matches.as_slice() {
+            [ds] => Ok(ds.id.clone()),
+            [] => Err(ServiceError::InvalidInput(format!("Data source '{}' not found", source))),
+            _ => Err(ServiceError::InvalidInput(
+                format!("Data source name '{}' is ambiguous", source)
+            )),
+        }
+    }
+}
+
+/// Params are substituted verbatim into the job config, including its SQL, so only
+/// scalars that cannot end a quoted literal or statement are accepted
+fn validate_params(params: &HashMap<String, serde_json::Value>) -> Result<(), ServiceError> {
+    for (name, value) in params {
+        let safe = match value {
+            serde_json::Value::String(s) => !s.contains(['\'', '"', '\\', ';'])
+                && !s.contains("--")
+                && !s.contains("/*"),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => true,
+            _ => false,
+        };
+        if !safe {
+            return Err(ServiceError::InvalidInput(format!(
+                "Param '{}' must be a number, a boolean or a string without quotes, backslashes, ';' or comments",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_job_definition() {
+        let content = r#"
+type: 'SQL'
+config:
+  source: 'default'
+  sql: |
+    CREATE TABLE IF NOT EXISTS t (id SERIAL PRIMARY KEY);
+
+    COMMENT ON TABLE t IS 'test';
+"#;
+        let job = JobService::parse(content).unwrap();
+
+        assert_eq!(job.job_type, "SQL");
+        assert_eq!(job.job_type.parse::<TaskType>().unwrap(), TaskType::from("sql"));
+        assert_eq!(job.config["source"], "default");
+        assert!(job.config["sql"].as_str().unwrap().contains("COMMENT ON TABLE t"));
+        assert!(job.dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_validate_params() {
+        let params = |value: serde_json::Value| HashMap::from([("shop".to_string(), value)]);
+
+        assert!(validate_params(&params(serde_json::json!("竞对 A"))).is_ok());
+        assert!(validate_params(&params(serde_json::json!(10))).is_ok());
+        assert!(validate_params(&params(serde_json::json!("x' OR '1'='1"))).is_err());
+        assert!(validate_params(&params(serde_json::json!("1; DROP TABLE t"))).is_err());
+        assert!(validate_params(&params(serde_json::json!("1 -- "))).is_err());
+        assert!(validate_params(&params(serde_json::json!(["a"]))).is_err());
+    }
+
+    #[test]
+    fn test_parse_repository_jobs() {
+        let jobs_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../jobs");
+
+        for entry in std::fs::read_dir(jobs_dir).unwrap() {
+            let path = entry.unwrap().path();
+            let content = std::fs::read_to_string(&path).unwrap();
+            let job = JobService::parse(&content)
+                .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+
+            assert!(job.job_type.parse::<TaskType>().is_ok(), "{}", path.display());
+            assert!(job.config.get("source").is_some(), "{}", path.display());
+        }
+    }
+}
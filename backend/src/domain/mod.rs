@@ -4,26 +4,38 @@ pub mod auth;
 pub mod data_source;
 pub mod storage;
 pub mod task;
+pub mod task_run;
 pub mod executor;
+pub mod job;
+pub mod scheduler;
 pub mod query;
 pub mod error;
+pub mod connection_pool;
 pub mod data_table;
 pub mod data_table_column;
 pub mod data_table_usage;
+pub mod workbook;
+pub mod view;
 
 pub use auth::AuthService;
 pub use data_source::{DataSourceService, CreateDataSourceRequest, UpdateDataSourceRequest, SqlExecutionResult};
 pub use storage::{StorageService, CreateStorageRequest, UpdateStorageRequest};
 pub use task::{TaskCenterClient, TaskService, Task, CreateTaskRequest, UpdateTaskRequest};
+pub use task_run::{TaskRunService, TaskRunQuery, TaskRunDetail};
 pub use executor::{
     ExecutorEngine, ExecutionResult, 
-    TaskMetadata, TaskType
+    TaskMetadata, TaskType, ExecutorRegistry, ExecutorInfo,
+    BackfillRequest, BackfillResult,
 };
-pub use query::{QueryService, QueryError};
+pub use job::{JobService, JobDefinition, RunJobRequest, BackfillJobRequest};
+pub use scheduler::TaskScheduler;
+pub use query::{QueryService, QueryError, QueryCache, RowFormat};
 pub use error::{AppError, AuthError, ServiceError, ExecutorError, ClientError};
+pub use connection_pool::ConnectionPools;
 pub use data_table::{DataTableService, CreateDataTableRequest, UpdateDataTableRequest, DataTableWithDetails};
 pub use data_table_column::{
     DataTableColumnService, CreateDataTableColumnRequest, UpdateDataTableColumnRequest,
     BatchCreateColumnsRequest,
 };
 pub use data_table_usage::{DataTableUsageService, UpsertDataTableUsageRequest, UpdateDataTableUsageRequest};
+pub use view::{ViewService, CreateViewRequest, UpdateViewRequest};
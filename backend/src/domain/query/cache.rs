@@ -0,0 +1,536 @@
+// Cache of view query results
+//
+// Results are keyed by tenant, view code and version, normalized params and row format, and
+// kept for the TTL of the view. Each result is tagged with the tables its SQL reads, so that
+// uploads and task runs writing one of those tables drop it, and with its view, so that
+// changing the view drops it. Results live in Redis when `cache.redis_url` is set, in
+// process memory otherwise.
+//
+// Dropping the results of a tag also bumps its generation. A query takes the generations
+// of its tags before it runs, and its result is not cached if any of them changed while it
+// ran, so rows read before a write are never cached after the write dropped the old ones.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use regex::Regex;
+use serde_json::Value;
+use crate::config::CacheConfig;
+use super::rows::RowFormat;
+
+const KEY_PREFIX: &str = "query_cache";
+
+// Cache a result unless the generation of one of its tags changed, and add it to the tags.
+// KEYS are the result key, its tags, then their generation keys; ARGV the result, its TTL,
+// then the expected generations. The TTL of a tag is only ever extended, a new key has
+// no TTL (-1).
+const PUT: &str = r#"
+local tags = (#KEYS - 1) / 2
+for i = 1, tags do
+    if tonumber(redis.call('GET', KEYS[1 + tags + i]) or '0') ~= tonumber(ARGV[2 + i]) then
+        return 0
+    end
+end
+redis.call('SET', KEYS[1], ARGV[1], 'EX', ARGV[2])
+for i = 1, tags do
+    redis.call('SADD', KEYS[1 + i], KEYS[1])
+    if redis.call('TTL', KEYS[1 + i]) < tonumber(ARGV[2]) then
+        redis.call('EXPIRE', KEYS[1 + i], ARGV[2])
+    end
+end
+return 1
+"#;
+
+pub struct QueryCache {
+    default_ttl_seconds: u64,
+    backend: Backend,
+}
+
+/// Tags of a view result, i.e. its tables and view, with their generations taken before
+/// its query runs
+pub struct Generation {
+    tags: Vec<String>,
+    // `None` when they could not be read, the result is then not cached
+    generations: Option<Vec<u64>>,
+}
+
+enum Backend {
+    Memory(Mutex<MemoryCache>),
+    Redis(RedisCache),
+}
+
+impl QueryCache {
+    pub fn new(config: &CacheConfig) -> Self {
+        let backend = match &config.redis_url {
+            Some(url) => match redis::Client::open(url.as_str()) {
+                Ok(client) => {
+                    log::info!("Caching query results in Redis");
+                    Backend::Redis(RedisCache { client, connection: tokio::sync::Mutex::new(None) })
+                }
+                Err(e) => {
+                    log::warn!("Invalid Redis URL, caching query results in memory: {}", e);
+                    Backend::Memory(Mutex::new(MemoryCache::new(config.max_entries)))
+                }
+            },
+            None => Backend::Memory(Mutex::new(MemoryCache::new(config.max_entries))),
+        };
+
+        Self { default_ttl_seconds: config.default_ttl_seconds, backend }
+    }
+
+    /// Cache key of a view query, of the current definition unless a version is given
+    pub fn key(
+        tenant_id: &str,
+        view_code: &str,
+        version: Option<i32>,
+        params: &Value,
+        format: &RowFormat,
+    ) -> String {
+        let format = serde_json::to_value(format).unwrap_or_default();
+        let version = version.map_or_else(|| "current".to_string(), |v| format!("v{}", v));
+        format!(
+            "{}:{}:result:{}:{}:{}:{}",
+            KEY_PREFIX, tenant_id, view_code, version, normalize(params), normalize(&format)
+        )
+    }
+
+    /// How long results of a view are cached, `None` when they are not
+    pub fn ttl(&self, view_ttl_seconds: Option<i32>) -> Option<Duration> {
+        let seconds = view_ttl_seconds.map_or(self.default_ttl_seconds, |s| s.max(0) as u64);
+        (seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Value> {
+        match &self.backend {
+            Backend::Memory(memory) => memory.lock().unwrap_or_else(PoisonError::into_inner).get(key),
+            Backend::Redis(redis) => redis.get(key).await,
+        }
+    }
+
+    /// Generation of a view result read from `tables`, to be taken before its query runs
+    pub async fn generation(&self, tenant_id: &str, view_code: &str, tables: &[String]) -> Generation {
+        let mut tags: Vec<String> = tables.iter().map(|table| table_tag(tenant_id, table)).collect();
+        tags.push(view_tag(tenant_id, view_code));
+        let generations = match &self.backend {
+            Backend::Memory(memory) => Some(memory.lock().unwrap_or_else(PoisonError::into_inner).generation(&tags)),
+            Backend::Redis(redis) => redis.generation(&tags).await,
+        };
+        Generation { tags, generations }
+    }
+
+    /// Cache a result, tagged with its view and the tables it was read from, unless one
+    /// of them was invalidated since `generation` was taken
+    pub async fn put(&self, key: &str, generation: &Generation, value: &Value, ttl: Duration) {
+        let Some(generations) = &generation.generations else { return };
+        let current = match &self.backend {
+            Backend::Memory(memory) => memory
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .put(key, generation.tags.clone(), generations, value.clone(), ttl),
+            Backend::Redis(redis) => redis.put(key, &generation.tags, generations, value, ttl).await,
+        };
+        if !current {
+            log::debug!("Not caching result {}, its tables or view changed while it was queried", key);
+        }
+    }
+
+    /// Drop the cached results read from any of the tables of a tenant
+    pub async fn invalidate_tables(&self, tenant_id: &str, tables: &[String]) {
+        let tags: Vec<String> = tables.iter().map(|table| table_tag(tenant_id, table)).collect();
+        let dropped = self.invalidate(&tags).await;
+        if dropped > 0 {
+            log::info!("Dropped {} cached query result(s) of tables {}", dropped, tables.join(", "));
+        }
+    }
+
+    /// Drop the cached results of a view, e.g. when its definition changes
+    pub async fn invalidate_view(&self, tenant_id: &str, view_code: &str) {
+        let dropped = self.invalidate(&[view_tag(tenant_id, view_code)]).await;
+        if dropped > 0 {
+            log::info!("Dropped {} cached query result(s) of view {}", dropped, view_code);
+        }
+    }
+
+    async fn invalidate(&self, tags: &[String]) -> usize {
+        match &self.backend {
+            Backend::Memory(memory) => memory
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .invalidate(tags),
+            Backend::Redis(redis) => redis.invalidate(tags).await,
+        }
+    }
+}
+
+/// Tables read by a view SQL, from its FROM and JOIN clauses, including comma-separated
+/// lists such as `FROM sales s, shops p`
+pub fn read_tables(sql: &str) -> Vec<String> {
+    static TABLES: OnceLock<Regex> = OnceLock::new();
+    static NEXT_TABLE: OnceLock<Regex> = OnceLock::new();
+    let regex = TABLES.get_or_init(|| Regex::new(
+        r#"(?i)\b(?:from|join)\s+((?:[\w$]+|"[^"]+"|`[^`]+`)(?:\.(?:[\w$]+|"[^"]+"|`[^`]+`))*)"#
+    ).expect("valid regex"));
+    // An optional alias of the previous table, then a comma and the next table
+    let next = NEXT_TABLE.get_or_init(|| Regex::new(
+        r#"^(?i)(?:\s+(?:as\s+)?(?:[\w$]+|"[^"]+"|`[^`]+`))?\s*,\s*((?:[\w$]+|"[^"]+"|`[^`]+`)(?:\.(?:[\w$]+|"[^"]+"|`[^`]+`))*)"#
+    ).expect("valid regex"));
+
+    let mut tables = Vec::new();
+    for captures in regex.captures_iter(sql) {
+        tables.push(table_name(&captures[1]));
+        let mut rest = &sql[captures.get(0).map_or(sql.len(), |m| m.end())..];
+        while let Some(listed) = next.captures(rest) {
+            tables.push(table_name(&listed[1]));
+            rest = &rest[listed.get(0).map_or(rest.len(), |m| m.end())..];
+        }
+    }
+    tables.sort();
+    tables.dedup();
+    tables
+}
+
+/// Table name without schema and quotes, lowercased unless quoted
+fn table_name(name: &str) -> String {
+    let last = name.rsplit('.').next().unwrap_or(name);
+    match last.strip_prefix(['"', '`']).and_then(|s| s.strip_suffix(['"', '`'])) {
+        Some(quoted) => quoted.to_string(),
+        None => last.to_lowercase(),
+    }
+}
+
+fn generation_key(tag: &str) -> String {
+    format!("{}:generation", tag)
+}
+
+fn table_tag(tenant_id: &str, table: &str) -> String {
+    format!("{}:{}:table:{}", KEY_PREFIX, tenant_id, table_name(table))
+}
+
+fn view_tag(tenant_id: &str, view_code: &str) -> String {
+    format!("{}:{}:view:{}", KEY_PREFIX, tenant_id, view_code)
+}
+
+/// Params as JSON with object keys sorted, so that equal params share a key
+fn normalize(params: &Value) -> String {
+    fn sorted(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                Value::Object(keys.into_iter().map(|k| (k.clone(), sorted(&map[k]))).collect())
+            }
+            Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+            other => other.clone(),
+        }
+    }
+
+    sorted(params).to_string()
+}
+
+struct MemoryCache {
+    max_entries: usize,
+    entries: HashMap<String, MemoryEntry>,
+    // Keys of the entries of each tag, i.e. table or view
+    tables: HashMap<String, HashSet<String>>,
+    // Number of times each tag was invalidated
+    generations: HashMap<String, u64>,
+}
+
+struct MemoryEntry {
+    value: Value,
+    tags: Vec<String>,
+    expires_at: Instant,
+}
+
+impl MemoryCache {
+    fn new(max_entries: usize) -> Self {
+        Self { max_entries, entries: HashMap::new(), tables: HashMap::new(), generations: HashMap::new() }
+    }
+
+    fn generation(&self, tags: &[String]) -> Vec<u64> {
+        tags.iter().map(|tag| self.generations.get(tag).copied().unwrap_or(0)).collect()
+    }
+
+    fn get(&mut self, key: &str) -> Option<Value> {
+        match self.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+            Some(_) => {
+                self.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put(&mut self, key: &str, tags: Vec<String>, generation: &[u64], value: Value, ttl: Duration) -> bool {
+        if self.generation(&tags) != generation {
+            return false;
+        }
+        if self.max_entries == 0 {
+            return true;
+        }
+        self.remove(key);
+
+        if self.entries.len() >= self.max_entries {
+            let now = Instant::now();
+            let expired: Vec<String> = self.entries
+                .iter()
+                .filter(|(_, entry)| entry.expires_at <= now)
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in expired {
+                self.remove(&key);
+            }
+        }
+        // Still full, make room by dropping the entry closest to expiry
+        if self.entries.len() >= self.max_entries {
+            let first = self.entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone());
+            if let Some(first) = first {
+                self.remove(&first);
+            }
+        }
+
+        for tag in &tags {
+            self.tables.entry(tag.clone()).or_default().insert(key.to_string());
+        }
+        self.entries.insert(key.to_string(), MemoryEntry {
+            value,
+            tags,
+            expires_at: Instant::now() + ttl,
+        });
+        true
+    }
+
+    fn invalidate(&mut self, tags: &[String]) -> usize {
+        for tag in tags {
+            *self.generations.entry(tag.clone()).or_default() += 1;
+        }
+        let keys: HashSet<String> = tags
+            .iter()
+            .filter_map(|tag| self.tables.remove(tag))
+            .flatten()
+            .collect();
+        keys.iter().filter(|key| self.remove(key)).count()
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        let Some(entry) = self.entries.remove(key) else { return false };
+        for tag in &entry.tags {
+            if let Some(keys) = self.tables.get_mut(tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tables.remove(tag);
+                }
+            }
+        }
+        true
+    }
+}
+
+struct RedisCache {
+    client: redis::Client,
+    // Reconnected on the next use after a failure
+    connection: tokio::sync::Mutex<Option<MultiplexedConnection>>,
+}
+
+impl RedisCache {
+    async fn connection(&self) -> Option<MultiplexedConnection> {
+        let mut connection = self.connection.lock().await;
+        if connection.is_none() {
+            match self.client.get_multiplexed_tokio_connection().await {
+                Ok(connected) => *connection = Some(connected),
+                Err(e) => log::warn!("Failed to connect to Redis, query results are not cached: {}", e),
+            }
+        }
+        connection.clone()
+    }
+
+    async fn disconnect(&self, e: redis::RedisError) {
+        log::warn!("Redis query cache failed: {}", e);
+        if e.is_connection_dropped() || e.is_io_error() {
+            *self.connection.lock().await = None;
+        }
+    }
+
+    async fn get(&self, key: &str) -> Option<Value> {
+        let mut connection = self.connection().await?;
+        match connection.get::<_, Option<String>>(key).await {
+            Ok(cached) => cached.and_then(|json| serde_json::from_str(&json).ok()),
+            Err(e) => {
+                self.disconnect(e).await;
+                None
+            }
+        }
+    }
+
+    async fn generation(&self, tags: &[String]) -> Option<Vec<u64>> {
+        let mut connection = self.connection().await?;
+        let keys: Vec<String> = tags.iter().map(|tag| generation_key(tag)).collect();
+        match redis::cmd("MGET").arg(&keys).query_async::<_, Vec<Option<u64>>>(&mut connection).await {
+            Ok(generations) => Some(generations.into_iter().map(Option::unwrap_or_default).collect()),
+            Err(e) => {
+                self.disconnect(e).await;
+                None
+            }
+        }
+    }
+
+    async fn put(&self, key: &str, tags: &[String], generation: &[u64], value: &Value, ttl: Duration) -> bool {
+        let Some(mut connection) = self.connection().await else { return true };
+        let seconds = ttl.as_secs().max(1) as usize;
+
+        // Tag sets outlive the results they point to, a dropped key is simply missing. A tag
+        // set is shared by results of different TTLs, so its TTL is only ever extended.
+        let mut cmd = redis::cmd("EVAL");
+        cmd.arg(PUT).arg(1 + 2 * tags.len()).arg(key).arg(tags);
+        for tag in tags {
+            cmd.arg(generation_key(tag));
+        }
+        cmd.arg(value.to_string()).arg(seconds).arg(generation);
+        match cmd.query_async::<_, i64>(&mut connection).await {
+            Ok(current) => current == 1,
+            Err(e) => {
+                self.disconnect(e).await;
+                true
+            }
+        }
+    }
+
+    async fn invalidate(&self, tags: &[String]) -> usize {
+        let Some(mut connection) = self.connection().await else { return 0 };
+
+        let mut keys = HashSet::new();
+        for tag in tags {
+            match connection.smembers::<_, Vec<String>>(tag).await {
+                Ok(members) => keys.extend(members),
+                Err(e) => {
+                    self.disconnect(e).await;
+                    return 0;
+                }
+            }
+        }
+
+        let keys: Vec<String> = keys.into_iter().collect();
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for tag in tags {
+            pipe.incr(generation_key(tag), 1).ignore();
+        }
+        pipe.del(tags).ignore();
+        if !keys.is_empty() {
+            pipe.del(&keys);
+        }
+        match pipe.query_async::<_, Vec<usize>>(&mut connection).await {
+            Ok(deleted) => deleted.into_iter().sum(),
+            Err(e) => {
+                self.disconnect(e).await;
+                0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::domain::query::rows::DecimalFormat;
+
+    #[tokio::test]
+    async fn test_memory_cache() {
+        let format = RowFormat::default();
+        assert_eq!(
+            QueryCache::key("t1", "sales", None, &json!({ "end": "1231", "start": "0101", "shops": ["b", "a"] }), &format),
+            QueryCache::key("t1", "sales", None, &json!({ "shops": ["b", "a"], "start": "0101", "end": "1231" }), &format)
+        );
+        assert_ne!(
+            QueryCache::key("t1", "sales", None, &json!({}), &format),
+            QueryCache::key("t1", "sales", None, &json!({}), &RowFormat { decimal: DecimalFormat::String, ..format.clone() })
+        );
+        assert_eq!(
+            read_tables("SELECT * FROM dw.Sales s JOIN \"Shops\" p ON s.id = p.id WHERE s.dt IN (SELECT dt FROM sales)"),
+            vec!["Shops".to_string(), "sales".to_string()]
+        );
+        assert_eq!(
+            read_tables("SELECT * FROM sales s, dw.shops AS p, \"Orders\" WHERE s.id = p.id ORDER BY s.dt, p.id"),
+            vec!["Orders".to_string(), "sales".to_string(), "shops".to_string()]
+        );
+        assert_eq!(
+            read_tables("SELECT a, b FROM sales GROUP BY a, b"),
+            vec!["sales".to_string()]
+        );
+
+        let cache = QueryCache::new(&CacheConfig { redis_url: None, default_ttl_seconds: 300, max_entries: 2 });
+        assert_eq!(cache.ttl(None), Some(Duration::from_secs(300)));
+        assert_eq!(cache.ttl(Some(0)), None);
+
+        let sales = QueryCache::key("t1", "sales", None, &json!({}), &format);
+        let shops = QueryCache::key("t1", "shops", None, &json!({}), &format);
+        let ttl = Duration::from_secs(60);
+        let generation = cache.generation("t1", "sales", &["dw.sales".to_string()]).await;
+        cache.put(&sales, &generation, &json!([1]), ttl).await;
+        let generation = cache.generation("t1", "shops", &["shops".to_string()]).await;
+        cache.put(&shops, &generation, &json!([2]), ttl).await;
+        assert_eq!(cache.get(&sales).await, Some(json!([1])));
+
+        // Writes of another tenant, or to other tables, keep the results
+        cache.invalidate_tables("t2", &["sales".to_string()]).await;
+        cache.invalidate_tables("t1", &["orders".to_string()]).await;
+        assert!(cache.get(&sales).await.is_some());
+
+        cache.invalidate_tables("t1", &["SALES".to_string()]).await;
+        assert_eq!(cache.get(&sales).await, None);
+        assert_eq!(cache.get(&shops).await, Some(json!([2])));
+
+        // A full cache drops the entry closest to expiry
+        let other = QueryCache::key("t1", "other", None, &json!({}), &format);
+        let generation = cache.generation("t1", "sales", &[]).await;
+        cache.put(&sales, &generation, &json!([1]), Duration::from_secs(120)).await;
+        let generation = cache.generation("t1", "other", &[]).await;
+        cache.put(&other, &generation, &json!([3]), ttl).await;
+        assert_eq!(cache.get(&shops).await, None);
+        assert!(cache.get(&sales).await.is_some());
+
+        assert_ne!(
+            QueryCache::key("t1", "sales", None, &json!({}), &format),
+            QueryCache::key("t1", "sales", Some(1), &json!({}), &format)
+        );
+
+        // Changing a view drops its results only
+        cache.invalidate_view("t1", "sales").await;
+        assert_eq!(cache.get(&sales).await, None);
+        assert_eq!(cache.get(&other).await, Some(json!([3])));
+    }
+
+    #[tokio::test]
+    async fn test_stale_result() {
+        let cache = QueryCache::new(&CacheConfig { redis_url: None, default_ttl_seconds: 300, max_entries: 10 });
+        let key = QueryCache::key("t1", "sales", None, &json!({}), &RowFormat::default());
+        let tables = ["sales".to_string()];
+        let ttl = Duration::from_secs(60);
+
+        // The table is written while the query runs, its rows are not cached
+        let generation = cache.generation("t1", "sales", &tables).await;
+        cache.invalidate_tables("t1", &tables).await;
+        cache.put(&key, &generation, &json!([1]), ttl).await;
+        assert_eq!(cache.get(&key).await, None);
+
+        // The same goes for a change of the view
+        let generation = cache.generation("t1", "sales", &tables).await;
+        cache.invalidate_view("t1", "sales").await;
+        cache.put(&key, &generation, &json!([1]), ttl).await;
+        assert_eq!(cache.get(&key).await, None);
+
+        // Writes of other tenants and tables don't matter
+        let generation = cache.generation("t1", "sales", &tables).await;
+        cache.invalidate_tables("t2", &tables).await;
+        cache.invalidate_tables("t1", &["orders".to_string()]).await;
+        cache.put(&key, &generation, &json!([2]), ttl).await;
+        assert_eq!(cache.get(&key).await, Some(json!([2])));
+    }
+}
@@ -1,7 +1,11 @@
 mod service;
 mod strategy;
 mod error;
+mod cache;
+pub mod rows;
 
 pub use service::QueryService;
 pub use strategy::{QueryStrategy, QueryContext, BuiltQuery};
 pub use error::QueryError;
+pub use cache::QueryCache;
+pub use rows::RowFormat;
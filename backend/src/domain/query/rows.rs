@@ -0,0 +1,245 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use sqlx::postgres::{PgHasArrayType, PgRow, PgTypeKind};
+use sqlx::types::{Decimal, Uuid};
+use sqlx::{Column, Decode, Postgres, Row, Type, TypeInfo};
+
+/// How query result values are rendered as JSON
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RowFormat {
+    pub decimal: DecimalFormat,
+    pub timestamp: TimestampFormat,
+    /// chrono format of timestamps (e.g. `%Y-%m-%d %H:%M:%S`), instead of `timestamp`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalFormat {
+    /// JSON number, exact up to about 15 significant digits
+    #[default]
+    Number,
+    /// String keeping every digit and the scale, e.g. `"12.50"`
+    String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// ISO 8601, `TIMESTAMPTZ` in UTC (`2025-01-01T08:00:00Z`)
+    #[default]
+    Iso,
+    /// Milliseconds since the Unix epoch, `TIMESTAMP` taken as UTC
+    EpochMillis,
+}
+
+impl RowFormat {
+    pub fn validate(&self) -> Result<(), String> {
+        use chrono::format::{Item, StrftimeItems};
+
+        match &self.timestamp_pattern {
+            Some(pattern) if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) => {
+                Err(format!("Invalid timestamp pattern: {}", pattern))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn decimal(&self, value: Decimal) -> Value {
+        match self.decimal {
+            DecimalFormat::String => Value::String(value.to_string()),
+            DecimalFormat::Number => {
+                let text = value.normalize().to_string();
+                match text.parse::<i64>() {
+                    Ok(integer) => Value::from(integer),
+                    Err(_) => float(text.parse().unwrap_or(f64::NAN)),
+                }
+            }
+        }
+    }
+
+    fn timestamp(&self, value: NaiveDateTime) -> Result<Value, String> {
+        match (&self.timestamp_pattern, self.timestamp) {
+            (Some(pattern), _) => render_pattern(value.format(pattern), pattern, "TIMESTAMP"),
+            (None, TimestampFormat::Iso) => Ok(Value::String(value.format("%Y-%m-%dT%H:%M:%S%.f").to_string())),
+            (None, TimestampFormat::EpochMillis) => Ok(Value::from(value.and_utc().timestamp_millis())),
+        }
+    }
+
+    fn timestamptz(&self, value: DateTime<Utc>) -> Result<Value, String> {
+        match (&self.timestamp_pattern, self.timestamp) {
+            (Some(pattern), _) => render_pattern(value.format(pattern), pattern, "TIMESTAMPTZ"),
+            (None, TimestampFormat::Iso) => Ok(Value::String(value.to_rfc3339_opts(SecondsFormat::AutoSi, true))),
+            (None, TimestampFormat::EpochMillis) => Ok(Value::from(value.timestamp_millis())),
+        }
+    }
+}
+
+/// Render a formatted timestamp; a pattern valid in itself can still fail on a value,
+/// e.g. `%z` on a TIMESTAMP, which has no offset
+fn render_pattern(formatted: impl std::fmt::Display, pattern: &str, type_name: &str) -> Result<Value, String> {
+    use std::fmt::Write;
+
+    let mut text = String::new();
+    write!(text, "{}", formatted)
+        .map_err(|_| format!("Timestamp pattern {} cannot format a {} value", pattern, type_name))?;
+    Ok(Value::String(text))
+}
+
+/// Column names of a row
+pub fn column_names(row: &PgRow) -> Vec<String> {
+    row.columns().iter().map(|column| column.name().to_string()).collect()
+}
+
+/// Row as a JSON object keyed by column name
+pub fn row_to_json(row: &PgRow, format: &RowFormat) -> Result<Map<String, Value>, String> {
+    Ok(column_names(row)
+        .into_iter()
+        .zip(row_values(row, format)?)
+        .collect())
+}
+
+/// Values of a row in column order
+pub fn row_values(row: &PgRow, format: &RowFormat) -> Result<Vec<Value>, String> {
+    (0..row.len()).map(|index| column_value(row, index, format)).collect()
+}
+
+/// Value of a column as JSON; NULL and types without a mapping (e.g. INTERVAL) are `null`
+pub fn column_value(row: &PgRow, index: usize, format: &RowFormat) -> Result<Value, String> {
+    let type_info = row.column(index).type_info();
+    let name = type_info.name();
+
+    if let Some(element) = name.strip_suffix("[]") {
+        return Ok(match element {
+            "BOOL" => array::<bool>(row, index, Value::Bool),
+            "INT2" => array::<i16>(row, index, Value::from),
+            "INT4" => array::<i32>(row, index, Value::from),
+            "INT8" => array::<i64>(row, index, Value::from),
+            "FLOAT4" => array::<f32>(row, index, |v| float(v.into())),
+            "FLOAT8" => array::<f64>(row, index, float),
+            "NUMERIC" => array::<Decimal>(row, index, |v| format.decimal(v)),
+            "TEXT" | "VARCHAR" | "CHAR" | "BPCHAR" | "NAME" => array::<String>(row, index, Value::String),
+            "DATE" => array::<NaiveDate>(row, index, |v| Value::String(v.to_string())),
+            "TIMESTAMP" => try_array::<NaiveDateTime>(row, index, |v| format.timestamp(v))?,
+            "TIMESTAMPTZ" => try_array::<DateTime<Utc>>(row, index, |v| format.timestamptz(v))?,
+            "UUID" => array::<Uuid>(row, index, |v| Value::String(v.to_string())),
+            "JSON" | "JSONB" => array::<Value>(row, index, |v| v),
+            _ => Value::Null,
+        });
+    }
+
+    Ok(match name {
+        "BOOL" => scalar::<bool>(row, index, Value::Bool),
+        "INT2" => scalar::<i16>(row, index, Value::from),
+        "INT4" => scalar::<i32>(row, index, Value::from),
+        "INT8" => scalar::<i64>(row, index, Value::from),
+        "FLOAT4" => scalar::<f32>(row, index, |v| float(v.into())),
+        "FLOAT8" => scalar::<f64>(row, index, float),
+        "NUMERIC" => scalar::<Decimal>(row, index, |v| format.decimal(v)),
+        "TEXT" | "VARCHAR" | "CHAR" | "BPCHAR" | "NAME" | "CITEXT" => scalar::<String>(row, index, Value::String),
+        "DATE" => scalar::<NaiveDate>(row, index, |v| Value::String(v.to_string())),
+        "TIME" => scalar::<NaiveTime>(row, index, |v| Value::String(v.to_string())),
+        "TIMESTAMP" => try_scalar::<NaiveDateTime>(row, index, |v| format.timestamp(v))?,
+        "TIMESTAMPTZ" => try_scalar::<DateTime<Utc>>(row, index, |v| format.timestamptz(v))?,
+        "UUID" => scalar::<Uuid>(row, index, |v| Value::String(v.to_string())),
+        "JSON" | "JSONB" => scalar::<Value>(row, index, |v| v),
+        "BYTEA" => scalar::<Vec<u8>>(row, index, |bytes| {
+            let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            Value::String(format!("\\x{}", hex))
+        }),
+        // Enum labels are sent as text
+        _ if matches!(type_info.kind(), PgTypeKind::Enum(_)) => row
+            .try_get_unchecked::<Option<String>, _>(index)
+            .ok()
+            .flatten()
+            .map_or(Value::Null, Value::String),
+        _ => Value::Null,
+    })
+}
+
+fn scalar<'r, T>(row: &'r PgRow, index: usize, render: impl Fn(T) -> Value) -> Value
+where
+    T: Decode<'r, Postgres> + Type<Postgres>,
+{
+    row.try_get::<Option<T>, _>(index)
+        .ok()
+        .flatten()
+        .map_or(Value::Null, render)
+}
+
+fn try_scalar<'r, T>(row: &'r PgRow, index: usize, render: impl Fn(T) -> Result<Value, String>) -> Result<Value, String>
+where
+    T: Decode<'r, Postgres> + Type<Postgres>,
+{
+    row.try_get::<Option<T>, _>(index)
+        .ok()
+        .flatten()
+        .map_or(Ok(Value::Null), render)
+}
+
+fn array<T>(row: &PgRow, index: usize, render: impl Fn(T) -> Value) -> Value
+where
+    T: for<'r> Decode<'r, Postgres> + Type<Postgres> + PgHasArrayType,
+{
+    try_array(row, index, |item| Ok(render(item))).unwrap_or(Value::Null)
+}
+
+fn try_array<T>(row: &PgRow, index: usize, render: impl Fn(T) -> Result<Value, String>) -> Result<Value, String>
+where
+    T: for<'r> Decode<'r, Postgres> + Type<Postgres> + PgHasArrayType,
+{
+    match row.try_get::<Option<Vec<Option<T>>>, _>(index) {
+        Ok(Some(items)) => items
+            .into_iter()
+            .map(|item| item.map_or(Ok(Value::Null), &render))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        _ => Ok(Value::Null),
+    }
+}
+
+/// NaN and infinities have no JSON number, they are rendered as `null`
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_render_values() {
+        let default = RowFormat::default();
+        assert_eq!(default.decimal(Decimal::from_str("12.500").unwrap()), serde_json::json!(12.5));
+        assert_eq!(default.decimal(Decimal::from_str("1200.00").unwrap()), serde_json::json!(1200));
+
+        let format: RowFormat = serde_json::from_value(serde_json::json!({
+            "decimal": "string",
+            "timestamp": "epoch_millis",
+        })).unwrap();
+        assert_eq!(format.decimal(Decimal::from_str("12.50").unwrap()), serde_json::json!("12.50"));
+
+        let at = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap().and_hms_opt(8, 30, 0).unwrap();
+        assert_eq!(default.timestamp(at), Ok(serde_json::json!("2025-01-02T08:30:00")));
+        assert_eq!(default.timestamptz(at.and_utc()), Ok(serde_json::json!("2025-01-02T08:30:00Z")));
+        assert_eq!(format.timestamp(at), Ok(serde_json::json!(1735806600000i64)));
+
+        let pattern = RowFormat { timestamp_pattern: Some("%Y/%m/%d %H:%M".to_string()), ..Default::default() };
+        assert!(pattern.validate().is_ok());
+        assert_eq!(pattern.timestamptz(at.and_utc()), Ok(serde_json::json!("2025/01/02 08:30")));
+        let invalid = RowFormat { timestamp_pattern: Some("%Y-%Q".to_string()), ..Default::default() };
+        assert!(invalid.validate().is_err());
+
+        // An offset is only known for TIMESTAMPTZ
+        let offset = RowFormat { timestamp_pattern: Some("%Y-%m-%d %z".to_string()), ..Default::default() };
+        assert!(offset.validate().is_ok());
+        assert_eq!(offset.timestamptz(at.and_utc()), Ok(serde_json::json!("2025-01-02 +0000")));
+        assert!(offset.timestamp(at).unwrap_err().contains("TIMESTAMP"));
+
+        assert_eq!(float(f64::NAN), Value::Null);
+    }
+}
@@ -1,11 +1,13 @@
 use serde_json::Value;
-use sqlx::{Pool, Postgres};
+use sqlx::{Executor, Pool, Postgres, Statement};
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::domain::DataSourceService;
+use crate::domain::{ConnectionPools, DataSourceService};
 use crate::repository::ViewRepository;
+use super::cache::{self, QueryCache};
 use super::error::QueryError;
+use super::rows::RowFormat;
 use super::strategy::{QueryStrategy, QueryContext};
 
 mod strategies;
@@ -14,11 +16,17 @@ use strategies::{ComparableCardStrategy};
 pub struct QueryService {
     data_source_service: Arc<DataSourceService>,
     view_repository: Arc<ViewRepository>,
+    connection_pools: Arc<ConnectionPools>,
+    query_cache: Arc<QueryCache>,
     strategies: HashMap<String, Box<dyn QueryStrategy>>,
 }
 
 impl QueryService {
-    pub fn new(data_source_service: Arc<DataSourceService>, view_repository: Arc<ViewRepository>) -> Self {
+    pub fn new(
+        data_source_service: Arc<DataSourceService>,
+        view_repository: Arc<ViewRepository>,
+        query_cache: Arc<QueryCache>,
+    ) -> Self {
         let mut strategies: HashMap<String, Box<dyn QueryStrategy>> = HashMap::new();
         
         // Register all strategies
@@ -28,8 +36,10 @@ impl QueryService {
         );
 
         Self {
+            connection_pools: data_source_service.connection_pools(),
             data_source_service,
             view_repository,
+            query_cache,
             strategies,
         }
     }
@@ -47,56 +57,7 @@ impl QueryService {
             .get(view)
             .ok_or_else(|| QueryError::StrategyNotFound(format!("View '{}' not found", view)))?;
 
-        // Get all data sources for the tenant
-        let data_sources = self
-            .data_source_service
-            .list_by_tenant(tenant_id)
-            .await
-            .map_err(|e| QueryError::DatabaseError(format!("Failed to get data sources: {}", e)))?;
-
-        // Create connection pools for each data source
-        let mut pools: HashMap<String, Pool<Postgres>> = HashMap::new();
-        
-        for ds in data_sources {
-            // Parse connection config
-            let config = ds.connection_config.as_object()
-                .ok_or_else(|| QueryError::InvalidInput("Invalid connection config".to_string()))?;
-
-            let host = config.get("host")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| QueryError::InvalidInput("Missing host in connection config".to_string()))?;
-            
-            let port = config.get("port")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(5432);
-            
-            let database = config.get("database")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| QueryError::InvalidInput("Missing database in connection config".to_string()))?;
-            
-            let username = config.get("username")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| QueryError::InvalidInput("Missing username in connection config".to_string()))?;
-            
-            let password = config.get("password")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| QueryError::InvalidInput("Missing password in connection config".to_string()))?;
-
-            // Build connection string
-            let connection_string = format!(
-                "postgres://{}:{}@{}:{}/{}",
-                username, password, host, port, database
-            );
-
-            // Create connection pool
-            let pool = sqlx::postgres::PgPoolOptions::new()
-                .max_connections(5)
-                .connect(&connection_string)
-                .await
-                .map_err(|e| QueryError::DatabaseError(format!("Failed to connect to {}: {}", ds.name, e)))?;
-
-            pools.insert(ds.name.clone(), pool);
-        }
+        let pools = self.postgres_pools(tenant_id).await?;
 
         // Create query context
         let context = QueryContext {
@@ -115,21 +76,163 @@ impl QueryService {
         tenant_id: &str,
         view_code: &str,
         params: Value,
+        format: RowFormat,
+        version: Option<i32>,
     ) -> Result<Value, QueryError> {
         // Find the view by code and tenant_id
-        let view = self
+        let mut view = self
             .view_repository
             .find_by_code_and_tenant(view_code, tenant_id)
             .await
             .map_err(|e| QueryError::DatabaseError(format!("Failed to query view: {}", e)))?
             .ok_or_else(|| QueryError::StrategyNotFound(format!("View '{}' not found for tenant '{}'", view_code, tenant_id)))?;
 
+        // Query the definition of an earlier version instead of the current one
+        if let Some(version) = version {
+            let pinned = self
+                .view_repository
+                .find_version(&view.id, version)
+                .await
+                .map_err(|e| QueryError::DatabaseError(format!("Failed to query view version: {}", e)))?
+                .ok_or_else(|| QueryError::StrategyNotFound(format!("Version {} of view '{}' not found", version, view_code)))?;
+            view.view_type = pinned.view_type;
+            view.view_sql = pinned.view_sql;
+        }
+
+        // Serve repeated queries from the cache until the TTL of the view passes or
+        // one of its tables is written
+        let ttl = self.query_cache.ttl(view.cache_ttl_seconds);
+        let cache_key = QueryCache::key(tenant_id, view_code, version, &params, &format);
+        if ttl.is_some() {
+            if let Some(cached) = self.query_cache.get(&cache_key).await {
+                return Ok(cached);
+            }
+        }
+
+        // Taken before the query runs, so that a write while it runs keeps its rows out of the cache
+        let tables = cache::read_tables(&view.view_sql);
+        let generation = match ttl {
+            Some(_) => Some(self.query_cache.generation(tenant_id, view_code, &tables).await),
+            None => None,
+        };
+
         // Build spec from view
         let spec = serde_json::json!({
-            "sql": view.view_sql
+            "sql": view.view_sql,
+            "format": format,
         });
 
         // Execute query using the view_type as strategy
-        self.execute_query(tenant_id, &view.view_type, params, spec).await
+        let result = self.execute_query(tenant_id, &view.view_type, params, spec).await?;
+
+        if let (Some(ttl), Some(generation)) = (ttl, generation) {
+            self.query_cache.put(&cache_key, &generation, &result, ttl).await;
+        }
+
+        Ok(result)
+    }
+
+    /// Check that a view can be queried before it is saved: its type names a registered
+    /// strategy, its SQL template compiles and PostgreSQL prepares the compiled SQL on a
+    /// data source of the tenant. The SQL is prepared only, never executed.
+    pub async fn validate_view(&self, tenant_id: &str, view_type: &str, view_sql: &str) -> Result<(), QueryError> {
+        if !self.strategies.contains_key(view_type) {
+            let mut registered: Vec<&str> = self.strategies.keys().map(|k| k.as_str()).collect();
+            registered.sort();
+            return Err(QueryError::InvalidInput(format!(
+                "Unknown view type '{}', expected one of: {}", view_type, registered.join(", ")
+            )));
+        }
+
+        // Set every parameter, so that all conditional blocks are compiled
+        let params: serde_json::Map<String, Value> = QueryContext::template_params(view_sql)?
+            .into_iter()
+            .map(|name| (name, Value::Null))
+            .collect();
+        let context = QueryContext {
+            data_sources: self.postgres_pools(tenant_id).await?,
+            tenant_id: tenant_id.to_string(),
+            params: Value::Object(params),
+            spec: serde_json::json!({ "sql": view_sql }),
+        };
+        let built = context.build_query()?;
+
+        // Views are queries; writes are also refused at query time by a read-only transaction
+        if !matches!(leading_keyword(&built.sql).as_str(), "SELECT" | "WITH" | "VALUES" | "TABLE") {
+            return Err(QueryError::InvalidInput("View SQL must be a SELECT query".to_string()));
+        }
+
+        // The view has to prepare on the source the strategy will query
+        let (name, pool) = context.default_data_source().ok_or_else(|| QueryError::InvalidInput(format!(
+            "Tenant '{}' has no PostgreSQL data source to query the view", tenant_id
+        )))?;
+        match pool.prepare(&built.sql).await {
+            Ok(statement) if statement.columns().is_empty() => Err(QueryError::InvalidInput(
+                format!("Invalid view SQL: {}: the SQL returns no columns", name)
+            )),
+            Ok(_) => Ok(()),
+            Err(e) => Err(QueryError::InvalidInput(format!("Invalid view SQL: {}: {}", name, e))),
+        }
+    }
+
+    /// Shared connection pools of the PostgreSQL data sources of a tenant, by name
+    async fn postgres_pools(&self, tenant_id: &str) -> Result<HashMap<String, Pool<Postgres>>, QueryError> {
+        // Get all data sources for the tenant
+        let data_sources = self
+            .data_source_service
+            .list_by_tenant(tenant_id)
+            .await
+            .map_err(|e| QueryError::DatabaseError(format!("Failed to get data sources: {}", e)))?;
+
+        let mut pools: HashMap<String, Pool<Postgres>> = HashMap::new();
+        
+        for ds in data_sources {
+            // Views query PostgreSQL sources only
+            if !matches!(ds.db_type.as_str(), "postgresql" | "PostgreSQL") {
+                continue;
+            }
+
+            let pool = self.connection_pools
+                .postgres(&ds)
+                .await
+                .map_err(|e| QueryError::DatabaseError(format!("Failed to connect to {}: {}", ds.name, e)))?;
+
+            pools.insert(ds.name.clone(), pool);
+        }
+
+        Ok(pools)
+    }
+}
+
+/// First keyword of a SQL statement, uppercased, after leading comments and parentheses
+fn leading_keyword(sql: &str) -> String {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '(');
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            break;
+        }
+    }
+    rest.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leading_keyword() {
+        assert_eq!(leading_keyword("  select 1"), "SELECT");
+        assert_eq!(leading_keyword("-- shops\n/* dropdown */ (SELECT 1) UNION (SELECT 2)"), "SELECT");
+        assert_eq!(leading_keyword("WITH d AS (SELECT 1) SELECT * FROM d"), "WITH");
+        assert_eq!(leading_keyword("DELETE FROM sales RETURNING *"), "DELETE");
+        assert_eq!(leading_keyword("-- only a comment"), "");
     }
 }
@@ -1,10 +1,16 @@
 use async_trait::async_trait;
 use serde_json::Value;
+use sqlx::postgres::PgRow;
 use sqlx::{Pool, Postgres};
 use std::collections::HashMap;
 use regex::Regex;
 
 use super::error::QueryError;
+use super::rows::RowFormat;
+
+// `[param_name:content]` conditional blocks and `{param_name}` placeholders of SQL templates
+const CONDITIONAL_PATTERN: &str = r"\[([^:\]]+):([^\]]*)\]";
+const PARAM_PATTERN: &str = r"\{([a-z0-9_]+)\}";
 
 /// QueryContext contains all the information needed for a query strategy
 pub struct QueryContext {
@@ -31,12 +37,12 @@ impl QueryContext {
     /// 
     /// Template syntax:
     /// - `{param_name}` - replaced with `$N` (PostgreSQL placeholder) and bound to the parameter value
-    /// - `<param_name:content>` - includes `content` only if `param_name` exists in params
+    /// - `[param_name:content]` - includes `content` only if `param_name` exists in params
     /// 
     /// # Example
     /// 
     /// ```
-    /// // spec.sql: "SELECT * FROM table WHERE id = {id} <name:AND name = {name}>"
+    /// // spec.sql: "SELECT * FROM table WHERE id = {id} [name:AND name = {name}]"
     /// // params: {"id": "1", "name": "test"}
     /// // Result: BuiltQuery { 
     /// //   sql: "SELECT * FROM table WHERE id = $1 AND name = $2",
@@ -45,7 +51,7 @@ impl QueryContext {
     /// ```
     /// 
     /// Then use it like:
-    /// ```
+    /// ```ignore
     /// let built = context.build_query()?;
     /// let mut query = sqlx::query(&built.sql);
     /// for param_name in &built.param_names {
@@ -61,7 +67,7 @@ impl QueryContext {
             .ok_or_else(|| QueryError::InvalidInput("Missing 'sql' in spec".to_string()))?;
 
         // Process conditional blocks: <param_name:content>
-        let conditional_regex = Regex::new(r"\[([^:\]]+):([^\]]*)\]")
+        let conditional_regex = Regex::new(CONDITIONAL_PATTERN)
             .map_err(|e| QueryError::InvalidInput(format!("Invalid regex: {}", e)))?;
         
         let mut param_names = Vec::new();
@@ -90,14 +96,13 @@ impl QueryContext {
         final_sql.push_str(&sql_template[last_end..]);
         
         // Second pass: replace {param_name} with $N and collect parameter names
-        let param_regex = Regex::new(r"\{([a-z0-9_]+)\}")
+        let param_regex = Regex::new(PARAM_PATTERN)
             .map_err(|e| QueryError::InvalidInput(format!("Invalid regex: {}", e)))?;
         
         let mut query_sql = String::new();
         let mut last_end = 0;
-        let mut param_index = 1;
         
-        for cap in param_regex.captures_iter(&final_sql) {
+        for (param_index, cap) in (1..).zip(param_regex.captures_iter(&final_sql)) {
             let full_match = cap.get(0).unwrap();
             let param_name = cap.get(1).unwrap().as_str();
             
@@ -107,7 +112,6 @@ impl QueryContext {
             // Replace with PostgreSQL placeholder ($1, $2, etc.)
             query_sql.push_str(&format!("${}", param_index));
             param_names.push(param_name.to_string());
-            param_index += 1;
             
             last_end = full_match.end();
         }
@@ -128,6 +132,61 @@ impl QueryContext {
         })
     }
     
+    /// Names of the parameters used by a SQL template, in conditional blocks or placeholders
+    pub fn template_params(sql_template: &str) -> Result<Vec<String>, QueryError> {
+        let mut names = Vec::new();
+        for pattern in [CONDITIONAL_PATTERN, PARAM_PATTERN] {
+            let regex = Regex::new(pattern)
+                .map_err(|e| QueryError::InvalidInput(format!("Invalid regex: {}", e)))?;
+            for cap in regex.captures_iter(sql_template) {
+                let name = cap[1].to_string();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Data source a view is queried on when it does not pick one: the first by name,
+    /// so that saving a view checks it against the same source
+    pub fn default_data_source(&self) -> Option<(&String, &Pool<Postgres>)> {
+        self.data_sources.iter().min_by_key(|(name, _)| name.as_str())
+    }
+
+    /// Rendering of result values, from `spec.format`
+    pub fn row_format(&self) -> Result<RowFormat, QueryError> {
+        let format: RowFormat = match self.spec.get("format") {
+            Some(format) => serde_json::from_value(format.clone())
+                .map_err(|e| QueryError::InvalidInput(format!("Invalid format: {}", e)))?,
+            None => RowFormat::default(),
+        };
+        format.validate().map_err(QueryError::InvalidInput)?;
+        Ok(format)
+    }
+
+    /// Run a query in a read-only transaction, so that the SQL of a view cannot write
+    /// to the data source, e.g. through `DELETE ... RETURNING` or a data-modifying `WITH`
+    pub async fn fetch_read_only(
+        &self,
+        pool: &Pool<Postgres>,
+        query: sqlx::query::Query<'_, Postgres, sqlx::postgres::PgArguments>,
+    ) -> Result<Vec<PgRow>, QueryError> {
+        let mut txn = pool.begin().await
+            .map_err(|e| QueryError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+        sqlx::query("SET TRANSACTION READ ONLY")
+            .execute(&mut *txn)
+            .await
+            .map_err(|e| QueryError::DatabaseError(format!("Failed to set transaction read only: {}", e)))?;
+
+        let rows = query.fetch_all(&mut *txn).await
+            .map_err(|e| QueryError::ExecutionError(format!("Query failed: {}", e)))?;
+
+        txn.rollback().await
+            .map_err(|e| QueryError::DatabaseError(format!("Failed to end transaction: {}", e)))?;
+        Ok(rows)
+    }
+
     /// Helper method to bind a parameter value to a query based on its JSON type
     pub fn bind_param<'q>(
         &self,
@@ -171,3 +230,41 @@ pub trait QueryStrategy: Send + Sync {
     /// Execute the query strategy and return the result
     async fn execute(&self, context: QueryContext) -> Result<Value, QueryError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_template_params() {
+        let sql = "SELECT * FROM sales WHERE dt BETWEEN {start} AND {end} [shop:AND shop = {shop}] [tag:AND tag = 'x']";
+        let names = QueryContext::template_params(sql).unwrap();
+        assert_eq!(names, vec!["shop", "tag", "start", "end"]);
+
+        // With every parameter set, all conditional blocks are compiled
+        let context = QueryContext {
+            data_sources: HashMap::new(),
+            tenant_id: "t1".to_string(),
+            params: Value::Object(names.into_iter().map(|name| (name, Value::Null)).collect()),
+            spec: json!({ "sql": sql }),
+        };
+        let built = context.build_query().unwrap();
+        assert_eq!(built.sql, "SELECT * FROM sales WHERE dt BETWEEN $1 AND $2 AND shop = $3 AND tag = 'x'");
+        assert_eq!(built.param_names, vec!["start", "end", "shop"]);
+    }
+
+    #[tokio::test]
+    async fn test_default_data_source() {
+        let pool = || sqlx::postgres::PgPoolOptions::new().connect_lazy("postgres://localhost/db").unwrap();
+        let context = QueryContext {
+            data_sources: ["sales", "archive", "orders"].into_iter().map(|name| (name.to_string(), pool())).collect(),
+            tenant_id: "t1".to_string(),
+            params: json!({}),
+            spec: json!({}),
+        };
+
+        // The same source whatever the order of the map
+        assert_eq!(context.default_data_source().map(|(name, _)| name.as_str()), Some("archive"));
+    }
+}
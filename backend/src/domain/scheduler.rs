@@ -0,0 +1,290 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use cron::Schedule;
+use sea_orm::Set;
+use crate::entities::task_schedule;
+use crate::repository::TaskScheduleRepository;
+use super::error::{AppError, ExecutorError};
+use super::executor::{ExecutorEngine, TaskMetadata, TaskSchedule, TaskType};
+use super::task::{Task, TaskService};
+
+// TaskScheduler - fires tasks of the task center on their cron schedule
+pub struct TaskScheduler {
+    task_service: Arc<TaskService>,
+    executor_engine: Arc<ExecutorEngine>,
+    schedule_repo: Arc<TaskScheduleRepository>,
+    poll_interval: Duration,
+    // Tasks with a scheduled run in progress
+    running: Arc<Mutex<HashSet<String>>>,
+}
+
+impl TaskScheduler {
+    pub fn new(
+        task_service: Arc<TaskService>,
+        executor_engine: Arc<ExecutorEngine>,
+        schedule_repo: Arc<TaskScheduleRepository>,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            task_service,
+            executor_engine,
+            schedule_repo,
+            poll_interval,
+            running: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Start polling the task center in the background
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            log::info!("Task scheduler started, polling every {:?}", self.poll_interval);
+
+            let mut interval = tokio::time::interval(self.poll_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                interval.tick().await;
+                self.tick().await;
+            }
+        })
+    }
+
+    async fn tick(&self) {
+        let tasks = match self.task_service.list().await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                log::warn!("Scheduler failed to list tasks: {}", e);
+                return;
+            }
+        };
+
+        let now = Local::now();
+        for task in tasks {
+            if task.metadata.get("schedule").is_none_or(|v| v.is_null()) {
+                continue;
+            }
+
+            let task_id = task.id.clone();
+            if let Err(e) = self.check_task(task, now).await {
+                log::warn!("Scheduler failed to check task {}: {}", task_id, e);
+            }
+        }
+    }
+
+    /// Fire the task if a scheduled time has passed since it last fired.
+    ///
+    /// Missed fire times (e.g. while the server was down) are collapsed into a single
+    /// run for the latest one. A task seen for the first time starts from `now`.
+    async fn check_task(&self, task: Task, now: DateTime<Local>) -> Result<(), AppError> {
+        let (task_type, metadata) = TaskType::resolve_task(task)?;
+        let schedule = match &metadata.schedule {
+            Some(schedule) => schedule.clone(),
+            None => return Ok(()),
+        };
+        let cron = parse_cron(&schedule.cron)?;
+
+        let state = match self.schedule_repo.find_by_task_id(&metadata.task_id).await? {
+            // A changed expression starts over from now
+            Some(state) if state.cron == schedule.cron => state,
+            Some(state) => {
+                let mut model: task_schedule::ActiveModel = state.into();
+                model.cron = Set(schedule.cron.clone());
+                model.last_fired_at = Set(now.naive_utc());
+                model.updated_at = Set(Utc::now().naive_utc());
+                self.schedule_repo.update(model).await?;
+                return Ok(());
+            }
+            None => {
+                self.schedule_repo.create(task_schedule::ActiveModel {
+                    task_id: Set(metadata.task_id.clone()),
+                    cron: Set(schedule.cron.clone()),
+                    last_fired_at: Set(now.naive_utc()),
+                    last_logical_date: Set(None),
+                    created_at: Set(Utc::now().naive_utc()),
+                    updated_at: Set(Utc::now().naive_utc()),
+                }).await?;
+                log::info!("Scheduled task {} with cron '{}'", metadata.task_id, schedule.cron);
+                return Ok(());
+            }
+        };
+
+        let last_fired = Utc.from_utc_datetime(&state.last_fired_at).with_timezone(&Local);
+        let fire_time = match due_fire_time(&cron, last_fired, now) {
+            Some(fire_time) => fire_time,
+            None => return Ok(()),
+        };
+        // An offset out of range skips the schedule, the error is logged by the caller
+        let logical_date = logical_date_for(&schedule, fire_time)?;
+
+        // Claim the fire time first, so a slot is never fired twice, by this or another instance
+        let claimed = self.schedule_repo
+            .claim_fire_time(&metadata.task_id, state.last_fired_at, fire_time.naive_utc(), &logical_date)
+            .await?;
+        if !claimed {
+            return Ok(());
+        }
+
+        let mut metadata = metadata;
+        metadata.logical_date = Some(logical_date.clone());
+
+        if !self.running.lock().unwrap_or_else(PoisonError::into_inner).insert(metadata.task_id.clone()) {
+            log::warn!(
+                "Skipping scheduled run of task {} at {}: previous run still in progress",
+                metadata.task_id, fire_time
+            );
+            self.executor_engine
+                .skip_task(&task_type, &metadata, "Previous scheduled run still in progress")
+                .await?;
+            return Ok(());
+        }
+
+        log::info!("Firing task {} for logical date {}", metadata.task_id, logical_date);
+        self.spawn_run(task_type, metadata, logical_date);
+        Ok(())
+    }
+
+    fn spawn_run(&self, task_type: TaskType, metadata: TaskMetadata, logical_date: String) {
+        let executor_engine = self.executor_engine.clone();
+        let guard = RunningGuard {
+            running: self.running.clone(),
+            task_id: metadata.task_id.clone(),
+        };
+
+        tokio::spawn(async move {
+            let _guard = guard;
+            let task_id = metadata.task_id.clone();
+
+            match executor_engine.execute_task(task_type, metadata).await {
+                Ok(result) if result.success => {
+                    log::info!("Scheduled run of task {} ({}) succeeded", task_id, logical_date);
+                }
+                Ok(result) => {
+                    log::warn!("Scheduled run of task {} ({}) failed: {}", task_id, logical_date, result.message);
+                }
+                Err(e) => {
+                    log::warn!("Scheduled run of task {} ({}) failed: {}", task_id, logical_date, e);
+                }
+            }
+        });
+    }
+}
+
+// Removes a task from the running set when its run ends, even on panic
+struct RunningGuard {
+    running: Arc<Mutex<HashSet<String>>>,
+    task_id: String,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.task_id);
+    }
+}
+
+/// Parse a cron expression, accepting the standard 5-field form as well as
+/// the 6/7-field form with seconds (and years) used by the `cron` crate
+pub fn parse_cron(expr: &str) -> Result<Schedule, ExecutorError> {
+    let expr = expr.trim();
+    let normalized = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+
+    Schedule::from_str(&normalized)
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Invalid cron expression '{}': {}", expr, e)))
+}
+
+/// Latest fire time in `(last_fired, now]`, if any
+pub fn due_fire_time(
+    schedule: &Schedule,
+    last_fired: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    schedule
+        .after(&last_fired)
+        .take_while(|fire_time| *fire_time <= now)
+        .last()
+}
+
+/// Logical date (yyyyMMdd) of a run fired at `fire_time`
+pub fn logical_date_for(schedule: &TaskSchedule, fire_time: DateTime<Local>) -> Result<String, ExecutorError> {
+    chrono::Duration::try_days(schedule.logical_date_offset)
+        .and_then(|offset| fire_time.date_naive().checked_add_signed(offset))
+        .map(|date| date.format("%Y%m%d").to_string())
+        .ok_or_else(|| ExecutorError::ExecutionFailed(format!(
+            "Logical date offset {} out of range",
+            schedule.logical_date_offset
+        )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(s: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local.from_local_datetime(&naive).unwrap()
+    }
+
+    #[test]
+    fn test_due_fire_time() {
+        let cron = parse_cron("30 2 * * *").unwrap();
+
+        // Not yet due
+        assert_eq!(due_fire_time(&cron, local("2025-10-26 02:30:00"), local("2025-10-27 02:29:59")), None);
+
+        // Due, and missed runs collapse into the latest one
+        assert_eq!(
+            due_fire_time(&cron, local("2025-10-24 02:30:00"), local("2025-10-27 08:00:00")),
+            Some(local("2025-10-27 02:30:00"))
+        );
+
+        assert!(parse_cron("0 */5 * * * *").is_ok());
+        assert!(parse_cron("not a cron").is_err());
+    }
+
+    #[test]
+    fn test_logical_date_for() {
+        let schedule = TaskSchedule { cron: "0 2 * * *".to_string(), logical_date_offset: -1 };
+        assert_eq!(logical_date_for(&schedule, local("2025-11-01 02:00:00")).unwrap(), "20251031");
+
+        let schedule = TaskSchedule { cron: "0 2 * * *".to_string(), logical_date_offset: 100_000_000 };
+        assert!(logical_date_for(&schedule, local("2025-11-01 02:00:00")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_claim_fire_time() {
+        use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+
+        // The second instance finds the fire time already moved and updates nothing
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([
+                MockExecResult { last_insert_id: 0, rows_affected: 1 },
+                MockExecResult { last_insert_id: 0, rows_affected: 0 },
+            ])
+            .into_connection();
+        let mock = match &db {
+            DatabaseConnection::MockDatabaseConnection(mock) => mock.clone(),
+            _ => unreachable!(),
+        };
+        let repo = TaskScheduleRepository::new(db);
+        let last_fired = local("2025-10-26 02:30:00").naive_utc();
+        let fire_time = local("2025-10-27 02:30:00").naive_utc();
+
+        assert!(repo.claim_fire_time("dws_daily", last_fired, fire_time, "20251026").await.unwrap());
+        assert!(!repo.claim_fire_time("dws_daily", last_fired, fire_time, "20251026").await.unwrap());
+
+        let sql = DatabaseConnection::MockDatabaseConnection(mock).into_transaction_log()[0].statements()[0].to_string();
+        assert!(sql.ends_with(&format!(
+            "WHERE \"task_schedules\".\"task_id\" = 'dws_daily' AND \"task_schedules\".\"last_fired_at\" = '{}'",
+            last_fired.format("%Y-%m-%d %H:%M:%S%.6f")
+        )), "{}", sql);
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use crate::domain::error::ClientError;
 
+// Task statuses maintained in the task center
+pub const TASK_STATUS_COMPLETED: &str = "completed";
+pub const TASK_STATUS_FAILED: &str = "failed";
+
 // Task data structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -182,4 +186,17 @@ impl TaskService {
     pub async fn delete(&self, id: String) -> Result<(), ClientError> {
         self.task_center_client.delete_task(&id).await
     }
+
+    /// Record the status of the latest run in the task center.
+    ///
+    /// Only the status is written, the metadata of the task is left as it is; dependency
+    /// checks read the run history instead.
+    pub async fn report_run(&self, id: &str, status: &str) -> Result<Task, ClientError> {
+        self.task_center_client.update_task(id, UpdateTaskRequest {
+            name: None,
+            status: Some(status.to_string()),
+            metadata: None,
+        }).await
+    }
 }
+
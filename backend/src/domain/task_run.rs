@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::entities::task_run;
+use crate::repository::{TaskRunFilter, TaskRunRepository};
+use super::error::{ExecutorError, ServiceError};
+use super::executor::ExecutionResult;
+use super::executor::template::parse_logical_date;
+
+// Task run statuses
+pub const TASK_RUN_STATUS_QUEUED: &str = "queued";
+pub const TASK_RUN_STATUS_RUNNING: &str = "running";
+pub const TASK_RUN_STATUS_SUCCEEDED: &str = "succeeded";
+pub const TASK_RUN_STATUS_FAILED: &str = "failed";
+pub const TASK_RUN_STATUS_CANCELLED: &str = "cancelled";
+pub const TASK_RUN_STATUS_SKIPPED: &str = "skipped";
+
+const DEFAULT_LIST_LIMIT: u64 = 100;
+const REDACTED: &str = "******";
+const MAX_LIST_LIMIT: u64 = 1000;
+
+// Filters of the run history list, within the tenant of the caller
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskRunQuery {
+    #[serde(default)]
+    pub task_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    // Inclusive range of logical dates (yyyyMMdd / yyyy-MM-dd)
+    #[serde(default)]
+    pub logical_date_from: Option<String>,
+    #[serde(default)]
+    pub logical_date_to: Option<String>,
+    // Start of the range of started_at, RFC 3339 or a date (yyyy-MM-dd / yyyyMMdd)
+    #[serde(default)]
+    pub from: Option<String>,
+    // End of the range of started_at, exclusive; a date includes the whole day
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+impl TaskRunQuery {
+    fn filter(&self, tenant_id: &str) -> Result<TaskRunFilter, ServiceError> {
+        let logical_date = |date: &str| parse_logical_date(date)
+            .map(|date| date.format("%Y%m%d").to_string())
+            .map_err(|e| ServiceError::InvalidInput(e.to_string()));
+
+        Ok(TaskRunFilter {
+            tenant_id: tenant_id.to_string(),
+            task_id: self.task_id.clone(),
+            status: self.status.clone(),
+            logical_date_from: self.logical_date_from.as_deref().map(logical_date).transpose()?,
+            logical_date_to: self.logical_date_to.as_deref().map(logical_date).transpose()?,
+            started_from: self.from.as_deref().map(|s| parse_time(s, false)).transpose()?,
+            started_to: self.to.as_deref().map(|s| parse_time(s, true)).transpose()?,
+            limit: self.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT),
+        })
+    }
+}
+
+// A run with the live progress of the executor, while it is running
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRunDetail {
+    #[serde(flatten)]
+    pub run: task_run::Model,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<serde_json::Value>,
+}
+
+// TaskRunService - run history of the executor engine
+pub struct TaskRunService {
+    repo: Arc<TaskRunRepository>,
+    // Latest progress reported by runs in progress on this instance
+    progress: RwLock<HashMap<String, serde_json::Value>>,
+}
+
+impl TaskRunService {
+    pub fn new(repo: Arc<TaskRunRepository>) -> Self {
+        Self {
+            repo,
+            progress: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record a new run with the given initial status; secrets in the config are redacted
+    pub async fn create(
+        &self,
+        task_id: &str,
+        task_type: &str,
+        tenant_id: Option<&str>,
+        logical_date: Option<&str>,
+        config: serde_json::Value,
+        status: &str,
+    ) -> Result<task_run::Model, DbErr> {
+        let now = Utc::now().naive_utc();
+        let logical_date = logical_date.map(normalize_logical_date);
+
+        self.repo.create(task_run::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            task_id: Set(task_id.to_string()),
+            task_type: Set(task_type.to_string()),
+            tenant_id: Set(tenant_id.map(str::to_string)),
+            logical_date: Set(logical_date),
+            config: Set(redact_secrets(config)),
+            status: Set(status.to_string()),
+            message: Set(None),
+            data: Set(None),
+            started_at: Set(now),
+            finished_at: Set(None),
+            created_at: Set(now),
+        }).await
+    }
+
+    /// Record that a queued run has started; false if the run is no longer queued,
+    /// e.g. because it was cancelled in the meantime
+    pub async fn mark_running(&self, id: &str) -> Result<bool, DbErr> {
+        self.repo.transition(
+            id,
+            TASK_RUN_STATUS_QUEUED,
+            TASK_RUN_STATUS_RUNNING,
+            Utc::now().naive_utc(),
+        ).await
+    }
+
+    pub fn set_progress(&self, id: &str, progress: serde_json::Value) {
+        if let Ok(mut map) = self.progress.write() {
+            map.insert(id.to_string(), progress);
+        }
+    }
+
+    /// Record that a run was skipped without executing
+    pub async fn skip(&self, id: &str, reason: &str) -> Result<task_run::Model, DbErr> {
+        let run = self.repo.find_by_id(id).await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("Task run {}", id)))?;
+
+        let mut model: task_run::ActiveModel = run.into();
+        model.status = Set(TASK_RUN_STATUS_SKIPPED.to_string());
+        model.message = Set(Some(reason.to_string()));
+        model.finished_at = Set(Some(Utc::now().naive_utc()));
+        self.repo.update(model).await
+    }
+
+    /// Record the outcome of a run
+    pub async fn finish(
+        &self,
+        id: &str,
+        result: &Result<ExecutionResult, ExecutorError>,
+    ) -> Result<task_run::Model, DbErr> {
+        let (status, message, data) = match result {
+            Ok(r) if r.success => (TASK_RUN_STATUS_SUCCEEDED, r.message.clone(), r.data.clone()),
+            Ok(r) => (TASK_RUN_STATUS_FAILED, r.message.clone(), r.data.clone()),
+            Err(e @ ExecutorError::Cancelled) => (TASK_RUN_STATUS_CANCELLED, e.to_string(), None),
+            // A rejected run never started, it is skipped for the unsatisfied dependencies
+            Err(ExecutorError::DependenciesNotSatisfied(unsatisfied)) => (
+                TASK_RUN_STATUS_SKIPPED,
+                format!("Dependencies not satisfied: {}", unsatisfied.join(", ")),
+                None,
+            ),
+            Err(e) => (TASK_RUN_STATUS_FAILED, e.to_string(), None),
+        };
+
+        if let Ok(mut map) = self.progress.write() {
+            map.remove(id);
+        }
+
+        let run = self.repo.find_by_id(id).await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("Task run {}", id)))?;
+
+        let mut model: task_run::ActiveModel = run.into();
+        model.status = Set(status.to_string());
+        model.message = Set(Some(message));
+        model.data = Set(data);
+        model.finished_at = Set(Some(Utc::now().naive_utc()));
+        self.repo.update(model).await
+    }
+
+    /// Fail runs left queued or running by a previous process.
+    ///
+    /// Queued and executing runs live in the memory of the executor that accepted them,
+    /// so after a restart nothing will ever finish them.
+    pub async fn fail_interrupted(&self, created_before: NaiveDateTime) -> Result<u64, DbErr> {
+        self.repo.finish_all(
+            &[TASK_RUN_STATUS_QUEUED, TASK_RUN_STATUS_RUNNING],
+            created_before,
+            TASK_RUN_STATUS_FAILED,
+            "Interrupted by an executor restart",
+        ).await
+    }
+
+    /// Run of a tenant; runs of other tenants are not found
+    pub async fn get(&self, tenant_id: &str, id: &str) -> Result<TaskRunDetail, ServiceError> {
+        let run = self.repo.find_by_id(id).await
+            .map_err(|_| ServiceError::InvalidInput("Failed to get task run".to_string()))?
+            .filter(|run| run.tenant_id.as_deref() == Some(tenant_id))
+            .ok_or(ServiceError::NotFound)?;
+
+        let progress = self.progress.read().ok()
+            .and_then(|map| map.get(id).cloned());
+
+        Ok(TaskRunDetail { run, progress })
+    }
+
+    /// Dependencies without a successful run, each with the reason.
+    ///
+    /// A dependency is satisfied by a successful run of the upstream task in the same tenant
+    /// for the same logical date; without a logical date, its latest run must have succeeded.
+    pub async fn unsatisfied_dependencies(
+        &self,
+        dependencies: &[String],
+        tenant_id: Option<&str>,
+        logical_date: Option<&str>,
+    ) -> Vec<String> {
+        let logical_date = logical_date.map(normalize_logical_date);
+        let mut unsatisfied = Vec::new();
+
+        for dependency in dependencies {
+            let reason = match &logical_date {
+                Some(date) => match self.repo
+                    .find_latest(dependency, tenant_id, Some(TASK_RUN_STATUS_SUCCEEDED), Some(date))
+                    .await
+                {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => format!("no successful run for {}", date),
+                    Err(e) => e.to_string(),
+                },
+                None => match self.repo.find_latest(dependency, tenant_id, None, None).await {
+                    Ok(Some(run)) if run.status == TASK_RUN_STATUS_SUCCEEDED => continue,
+                    Ok(Some(run)) => format!("last run {}", run.status),
+                    Ok(None) => "never run".to_string(),
+                    Err(e) => e.to_string(),
+                },
+            };
+            unsatisfied.push(format!("{} ({})", dependency, reason));
+        }
+
+        unsatisfied
+    }
+
+    /// Whether a later logical date of the task has already run, e.g. while backfilling
+    pub async fn is_superseded(
+        &self,
+        task_id: &str,
+        tenant_id: Option<&str>,
+        logical_date: Option<&str>,
+    ) -> bool {
+        let Some(logical_date) = logical_date.map(normalize_logical_date) else { return false };
+        self.repo.exists_later(task_id, tenant_id, &logical_date).await.unwrap_or(false)
+    }
+
+    /// Runs of a tenant matching the query, most recent first
+    pub async fn list(&self, tenant_id: &str, query: TaskRunQuery) -> Result<Vec<task_run::Model>, ServiceError> {
+        let filter = query.filter(tenant_id)?;
+
+        self.repo.find_filtered(&filter).await
+            .map_err(|_| ServiceError::InvalidInput("Failed to list task runs".to_string()))
+    }
+}
+
+/// Logical date as yyyyMMdd, so that logical dates compare as strings
+fn normalize_logical_date(date: &str) -> String {
+    match parse_logical_date(date) {
+        Ok(date) => date.format("%Y%m%d").to_string(),
+        Err(_) => date.to_string(),
+    }
+}
+
+/// Config with the values of headers, auth settings and secret-like keys (passwords,
+/// tokens, keys) replaced, as it is stored and returned with the run history
+pub fn redact_secrets(config: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    fn is_secret(key: &str) -> bool {
+        let key = key.to_lowercase();
+        matches!(key.as_str(), "headers" | "auth") || key.starts_with("auth_")
+            || ["authorization", "password", "passwd", "secret", "token", "api_key", "apikey", "access_key", "credential", "cookie"]
+                .iter()
+                .any(|secret| key.contains(secret))
+    }
+
+    fn redact(value: Value) -> Value {
+        match value {
+            Value::Object(obj) => Value::Object(obj.into_iter()
+                .map(|(key, value)| match value {
+                    Value::Null => (key, Value::Null),
+                    _ if is_secret(&key) => (key, Value::String(REDACTED.to_string())),
+                    value => (key, redact(value)),
+                })
+                .collect()),
+            Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+            other => other,
+        }
+    }
+
+    redact(config)
+}
+
+/// Parse a range bound into a UTC timestamp.
+///
+/// Dates are taken in server local time; as an end bound a date covers the whole day.
+fn parse_time(value: &str, end: bool) -> Result<NaiveDateTime, ServiceError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.naive_utc());
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d"))
+        .map_err(|_| ServiceError::InvalidInput(format!("Invalid time '{}'", value)))?;
+    let date = if end { date + Duration::days(1) } else { date };
+
+    let midnight = date.and_hms_opt(0, 0, 0)
+        .and_then(|t| Local.from_local_datetime(&t).earliest())
+        .ok_or_else(|| ServiceError::InvalidInput(format!("Invalid time '{}'", value)))?;
+    Ok(midnight.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_secrets() {
+        let config = serde_json::json!({
+            "url": "https://api.example.com/orders?date=20251026",
+            "headers": { "Authorization": "Bearer abc", "Accept": "application/json" },
+            "body": { "user": "etl", "password": "p@ss", "refresh_token": "xyz" },
+            "targets": [{ "auth_config": { "access_key": "k" }, "table": "orders" }],
+            "api_key": null,
+        });
+
+        let redacted = redact_secrets(config);
+        assert_eq!(redacted["url"], "https://api.example.com/orders?date=20251026");
+        assert_eq!(redacted["headers"], REDACTED);
+        assert_eq!(redacted["body"]["user"], "etl");
+        assert_eq!(redacted["body"]["password"], REDACTED);
+        assert_eq!(redacted["body"]["refresh_token"], REDACTED);
+        assert_eq!(redacted["targets"][0]["auth_config"], REDACTED);
+        assert_eq!(redacted["targets"][0]["table"], "orders");
+        assert_eq!(redacted["api_key"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let utc = parse_time("2025-10-26T08:00:00+08:00", false).unwrap();
+        assert_eq!(utc, NaiveDate::from_ymd_opt(2025, 10, 26).unwrap().and_hms_opt(0, 0, 0).unwrap());
+
+        // A date is a local day; as an end bound it covers the whole day
+        let start = parse_time("2025-10-26", false).unwrap();
+        assert_eq!(parse_time("20251026", false).unwrap(), start);
+        assert_eq!(parse_time("2025-10-26", true).unwrap() - start, Duration::days(1));
+
+        assert!(parse_time("yesterday", false).is_err());
+    }
+
+    #[test]
+    fn test_filter_runs() {
+        let query: TaskRunQuery = serde_json::from_value(serde_json::json!({
+            "task_id": "dwd_sales",
+            "status": "failed",
+            "logical_date_from": "2025-10-01",
+            "logical_date_to": "20251026",
+            "limit": 5000,
+        })).unwrap();
+        let filter = query.filter("t1").unwrap();
+        assert_eq!(filter.logical_date_from.as_deref(), Some("20251001"));
+        assert_eq!(filter.limit, MAX_LIST_LIMIT);
+
+        let sql = filter.select().build(DbBackend::Postgres).to_string();
+        assert_eq!(
+            sql,
+            "SELECT \"task_runs\".\"id\", \"task_runs\".\"task_id\", \"task_runs\".\"task_type\", \"task_runs\".\"tenant_id\", \
+             \"task_runs\".\"logical_date\", \"task_runs\".\"config\", \"task_runs\".\"status\", \"task_runs\".\"message\", \
+             \"task_runs\".\"data\", \"task_runs\".\"started_at\", \"task_runs\".\"finished_at\", \"task_runs\".\"created_at\" \
+             FROM \"task_runs\" WHERE \"task_runs\".\"tenant_id\" = 't1' AND \"task_runs\".\"task_id\" = 'dwd_sales' \
+             AND \"task_runs\".\"status\" = 'failed' AND \"task_runs\".\"logical_date\" >= '20251001' \
+             AND \"task_runs\".\"logical_date\" <= '20251026' ORDER BY \"task_runs\".\"started_at\" DESC LIMIT 1000"
+        );
+
+        // Without filters only the tenant and the default limit apply
+        let sql = TaskRunQuery::default().filter("t1").unwrap().select().build(DbBackend::Postgres).to_string();
+        assert!(sql.ends_with("WHERE \"task_runs\".\"tenant_id\" = 't1' ORDER BY \"task_runs\".\"started_at\" DESC LIMIT 100"));
+
+        let invalid = TaskRunQuery { logical_date_from: Some("2025-13-01".to_string()), ..Default::default() };
+        assert!(invalid.filter("t1").is_err());
+    }
+
+    fn run(task_id: &str, logical_date: &str, status: &str) -> task_run::Model {
+        let at = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap().and_hms_opt(2, 0, 0).unwrap();
+        task_run::Model {
+            id: format!("{}-{}", task_id, logical_date),
+            task_id: task_id.to_string(),
+            task_type: "sql".to_string(),
+            tenant_id: Some("t1".to_string()),
+            logical_date: Some(logical_date.to_string()),
+            config: serde_json::json!({}),
+            status: status.to_string(),
+            message: None,
+            data: None,
+            started_at: at,
+            finished_at: Some(at),
+            created_at: at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsatisfied_dependencies() {
+        // Successful run of dwd_a for the date, none of dwd_b, then the latest runs of both
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![run("dwd_a", "20251026", TASK_RUN_STATUS_SUCCEEDED)]])
+            .append_query_results([Vec::<task_run::Model>::new()])
+            .append_query_results([vec![run("dwd_a", "20251027", TASK_RUN_STATUS_SUCCEEDED)]])
+            .append_query_results([vec![run("dwd_b", "20251027", TASK_RUN_STATUS_FAILED)]])
+            .into_connection();
+        let service = TaskRunService::new(Arc::new(TaskRunRepository::new(db)));
+        let dependencies = vec!["dwd_a".to_string(), "dwd_b".to_string()];
+
+        // Each logical date of a backfill is checked on its own
+        assert_eq!(
+            service.unsatisfied_dependencies(&dependencies, Some("t1"), Some("2025-10-26")).await,
+            vec!["dwd_b (no successful run for 20251026)".to_string()]
+        );
+        assert_eq!(
+            service.unsatisfied_dependencies(&dependencies, Some("t1"), None).await,
+            vec!["dwd_b (last run failed)".to_string()]
+        );
+
+        // Runs of another tenant or outside any tenant do not count
+        let sql = TaskRunRepository::runs_of("dwd_a", None).build(DbBackend::Postgres).to_string();
+        assert!(sql.ends_with("WHERE \"task_runs\".\"task_id\" = 'dwd_a' AND \"task_runs\".\"tenant_id\" IS NULL"));
+    }
+
+    #[tokio::test]
+    async fn test_is_superseded() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![run("dws_daily", "20251027", TASK_RUN_STATUS_SUCCEEDED)]])
+            .append_query_results([Vec::<task_run::Model>::new()])
+            .into_connection();
+        let service = TaskRunService::new(Arc::new(TaskRunRepository::new(db)));
+
+        assert!(service.is_superseded("dws_daily", Some("t1"), Some("20251001")).await);
+        assert!(!service.is_superseded("dws_daily", Some("t1"), Some("20251027")).await);
+        // Runs without a logical date are always the latest
+        assert!(!service.is_superseded("dws_daily", Some("t1"), None).await);
+    }
+
+    #[tokio::test]
+    async fn test_fail_interrupted() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([MockExecResult { last_insert_id: 0, rows_affected: 2 }])
+            .into_connection();
+        // Keep a handle on the mock to read the statement log afterwards
+        let mock = match &db {
+            DatabaseConnection::MockDatabaseConnection(mock) => mock.clone(),
+            _ => unreachable!(),
+        };
+        let service = TaskRunService::new(Arc::new(TaskRunRepository::new(db)));
+        let started = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap().and_hms_opt(2, 0, 0).unwrap();
+
+        assert_eq!(service.fail_interrupted(started).await.unwrap(), 2);
+
+        let sql = DatabaseConnection::MockDatabaseConnection(mock).into_transaction_log()[0].statements()[0].to_string();
+        assert!(sql.starts_with("UPDATE \"task_runs\" SET \"status\" = 'failed', \"message\" = 'Interrupted by an executor restart'"));
+        assert!(sql.ends_with(
+            "WHERE \"task_runs\".\"status\" IN ('queued', 'running') AND \"task_runs\".\"created_at\" < '2025-10-27 02:00:00.000000'"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mark_running() {
+        // The second run was cancelled while queued and is left alone
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([
+                MockExecResult { last_insert_id: 0, rows_affected: 1 },
+                MockExecResult { last_insert_id: 0, rows_affected: 0 },
+            ])
+            .into_connection();
+        let mock = match &db {
+            DatabaseConnection::MockDatabaseConnection(mock) => mock.clone(),
+            _ => unreachable!(),
+        };
+        let service = TaskRunService::new(Arc::new(TaskRunRepository::new(db)));
+
+        assert!(service.mark_running("run-1").await.unwrap());
+        assert!(!service.mark_running("run-2").await.unwrap());
+
+        let sql = DatabaseConnection::MockDatabaseConnection(mock).into_transaction_log()[0].statements()[0].to_string();
+        assert!(sql.starts_with("UPDATE \"task_runs\" SET \"status\" = 'running'"), "{}", sql);
+        assert!(sql.ends_with("WHERE \"task_runs\".\"id\" = 'run-1' AND \"task_runs\".\"status\" = 'queued'"), "{}", sql);
+    }
+
+    #[tokio::test]
+    async fn test_finish_rejected_run() {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![run("dws_daily", "20251026", TASK_RUN_STATUS_QUEUED)]])
+            .append_query_results([vec![run("dws_daily", "20251026", TASK_RUN_STATUS_SKIPPED)]])
+            .into_connection();
+        let mock = match &db {
+            DatabaseConnection::MockDatabaseConnection(mock) => mock.clone(),
+            _ => unreachable!(),
+        };
+        let service = TaskRunService::new(Arc::new(TaskRunRepository::new(db)));
+
+        let rejected = Err(ExecutorError::DependenciesNotSatisfied(vec!["dwd_a (never run)".to_string()]));
+        service.finish("dws_daily-20251026", &rejected).await.unwrap();
+
+        let sql = DatabaseConnection::MockDatabaseConnection(mock).into_transaction_log()[1].statements()[0].to_string();
+        assert!(sql.contains("\"status\" = 'skipped'"), "{}", sql);
+        assert!(sql.contains("\"message\" = 'Dependencies not satisfied: dwd_a (never run)'"), "{}", sql);
+    }
+}
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use crate::entities::{view, view_version};
+use crate::repository::ViewRepository;
+use super::error::ServiceError;
+use super::query::{QueryCache, QueryError, QueryService};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateViewRequest {
+    pub view_code: String,
+    pub view_type: String,
+    pub view_sql: String,
+    pub cache_ttl_seconds: Option<i32>,
+    #[serde(skip_deserializing)]
+    pub tenant_id: Option<String>,
+    // User ID of the token, recorded as the author of the first version
+    #[serde(skip_deserializing)]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateViewRequest {
+    pub view_type: Option<String>,
+    pub view_sql: Option<String>,
+    pub cache_ttl_seconds: Option<i32>,
+}
+
+pub struct ViewService {
+    view_repo: Arc<ViewRepository>,
+    query_service: Arc<QueryService>,
+    query_cache: Arc<QueryCache>,
+}
+
+impl ViewService {
+    pub fn new(
+        view_repo: Arc<ViewRepository>,
+        query_service: Arc<QueryService>,
+        query_cache: Arc<QueryCache>,
+    ) -> Self {
+        Self {
+            view_repo,
+            query_service,
+            query_cache,
+        }
+    }
+
+    pub async fn create(&self, req: CreateViewRequest) -> Result<view::Model, ServiceError> {
+        if req.view_code.trim().is_empty() {
+            return Err(ServiceError::InvalidInput("View code cannot be empty".to_string()));
+        }
+
+        let tenant_id = req.tenant_id.ok_or(ServiceError::InvalidInput("Tenant ID is required".to_string()))?;
+        let author = req.author.ok_or(ServiceError::Unauthorized)?;
+
+        // Check if view with same code already exists
+        if self.view_repo
+            .find_by_code_and_tenant(&req.view_code, &tenant_id)
+            .await?
+            .is_some()
+        {
+            return Err(ServiceError::AlreadyExists);
+        }
+
+        // Reject views that cannot be queried
+        self.validate(&tenant_id, &req.view_type, &req.view_sql, req.cache_ttl_seconds).await?;
+
+        let now = Utc::now().naive_utc();
+        let id = format!("{}-{}", tenant_id, uuid::Uuid::new_v4());
+
+        let view = view::Model {
+            id,
+            tenant_id,
+            view_code: req.view_code,
+            view_type: req.view_type,
+            view_sql: req.view_sql,
+            cache_ttl_seconds: req.cache_ttl_seconds,
+            created_at: now,
+            updated_at: now,
+        };
+        let version = version_of(&view, 1, Some(author), None);
+
+        // A view created concurrently with the same code fails on the unique index
+        self.view_repo.create(view.into_active_model(), version).await
+            .map_err(|e| match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => ServiceError::AlreadyExists,
+                _ => ServiceError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_by_tenant(&self, tenant_id: &str) -> Result<Vec<view::Model>, ServiceError> {
+        Ok(self.view_repo.find_by_tenant(tenant_id).await?)
+    }
+
+    pub async fn get(&self, tenant_id: &str, view_code: &str) -> Result<view::Model, ServiceError> {
+        self.view_repo.find_by_code_and_tenant(view_code, tenant_id).await?
+            .ok_or(ServiceError::NotFound)
+    }
+
+    /// Update a view; a change of `view_type` or `view_sql` is recorded as a new version
+    pub async fn update(
+        &self,
+        tenant_id: &str,
+        view_code: &str,
+        req: UpdateViewRequest,
+        author: &str,
+    ) -> Result<view::Model, ServiceError> {
+        let existing = self.get(tenant_id, view_code).await?;
+
+        let mut changed = existing.clone();
+        if let Some(view_type) = req.view_type {
+            changed.view_type = view_type;
+        }
+        if let Some(view_sql) = req.view_sql {
+            changed.view_sql = view_sql;
+        }
+        if req.cache_ttl_seconds.is_some() {
+            changed.cache_ttl_seconds = req.cache_ttl_seconds;
+        }
+
+        // Only a changed definition is checked again against the data sources
+        if is_redefined(&existing, &changed) {
+            self.validate(tenant_id, &changed.view_type, &changed.view_sql, changed.cache_ttl_seconds).await?;
+        } else {
+            validate_ttl(changed.cache_ttl_seconds)?;
+        }
+
+        self.save(existing, changed, author, None).await
+    }
+
+    /// Restore the definition of a version, recorded as a new version
+    pub async fn rollback(
+        &self,
+        tenant_id: &str,
+        view_code: &str,
+        version: i32,
+        author: &str,
+    ) -> Result<view::Model, ServiceError> {
+        let existing = self.get(tenant_id, view_code).await?;
+        let target = self.get_version(tenant_id, view_code, version).await?;
+
+        // The version was checked when it was saved; it is not prepared again, so that a
+        // view can be brought back while a data source is unavailable
+        let mut changed = existing.clone();
+        changed.view_type = target.view_type;
+        changed.view_sql = target.view_sql;
+
+        self.save(existing, changed, author, Some(version)).await
+    }
+
+    /// Versions of a view, most recent first
+    pub async fn list_versions(
+        &self,
+        tenant_id: &str,
+        view_code: &str,
+    ) -> Result<Vec<view_version::Model>, ServiceError> {
+        let view = self.get(tenant_id, view_code).await?;
+        Ok(self.view_repo.find_versions(&view.id).await?)
+    }
+
+    pub async fn get_version(
+        &self,
+        tenant_id: &str,
+        view_code: &str,
+        version: i32,
+    ) -> Result<view_version::Model, ServiceError> {
+        let view = self.get(tenant_id, view_code).await?;
+        self.view_repo.find_version(&view.id, version).await?
+            .ok_or(ServiceError::NotFound)
+    }
+
+    pub async fn delete(&self, tenant_id: &str, view_code: &str) -> Result<(), ServiceError> {
+        let existing = self.get(tenant_id, view_code).await?;
+
+        self.view_repo.delete(&existing.id).await?;
+
+        self.query_cache.invalidate_view(tenant_id, view_code).await;
+
+        Ok(())
+    }
+
+    // Save the changed view, with a new version when its definition changed
+    async fn save(
+        &self,
+        existing: view::Model,
+        changed: view::Model,
+        author: &str,
+        restored_from: Option<i32>,
+    ) -> Result<view::Model, ServiceError> {
+        let redefined = is_redefined(&existing, &changed);
+        let mut model: view::ActiveModel = existing.clone().into();
+        model.cache_ttl_seconds = Set(changed.cache_ttl_seconds);
+        model.updated_at = Set(Utc::now().naive_utc());
+
+        let mut versions = Vec::new();
+        if redefined {
+            let latest = self.view_repo.find_latest_version(&existing.id).await?;
+            let next = match latest {
+                Some(latest) => latest.version + 1,
+                None => {
+                    // Views inserted without the API get their previous definition as version 1
+                    versions.push(version_of(&existing, 1, None, None));
+                    2
+                }
+            };
+            let changed = view::Model { updated_at: *model.updated_at.as_ref(), ..changed };
+            versions.push(version_of(&changed, next, Some(author.to_string()), restored_from));
+
+            // The definition is only written together with its version
+            model.view_type = Set(changed.view_type);
+            model.view_sql = Set(changed.view_sql);
+        }
+
+        // Versions are numbered from the latest one read above; a concurrent change
+        // taking the same number fails on the unique key and the whole update rolls back
+        let view = self.view_repo.update(model, versions).await
+            .map_err(|e| match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => ServiceError::Conflict(
+                    "The view was changed concurrently, reload it and try again".to_string()
+                ),
+                _ => ServiceError::DatabaseError(e),
+            })?;
+
+        // Results cached for the previous definition are stale
+        self.query_cache.invalidate_view(&view.tenant_id, &view.view_code).await;
+
+        Ok(view)
+    }
+
+    async fn validate(
+        &self,
+        tenant_id: &str,
+        view_type: &str,
+        view_sql: &str,
+        cache_ttl_seconds: Option<i32>,
+    ) -> Result<(), ServiceError> {
+        validate_ttl(cache_ttl_seconds)?;
+        self.query_service
+            .validate_view(tenant_id, view_type, view_sql)
+            .await
+            .map_err(|e| match e {
+                QueryError::InvalidInput(msg) => ServiceError::InvalidInput(msg),
+                QueryError::DatabaseError(msg) => ServiceError::DatabaseError(DbErr::Custom(msg)),
+                other => ServiceError::InvalidInput(other.to_string()),
+            })
+    }
+}
+
+fn validate_ttl(cache_ttl_seconds: Option<i32>) -> Result<(), ServiceError> {
+    match cache_ttl_seconds {
+        Some(seconds) if seconds < 0 => Err(ServiceError::InvalidInput(
+            "Cache TTL cannot be negative".to_string()
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn is_redefined(existing: &view::Model, changed: &view::Model) -> bool {
+    existing.view_type != changed.view_type || existing.view_sql != changed.view_sql
+}
+
+fn version_of(
+    view: &view::Model,
+    version: i32,
+    author: Option<String>,
+    restored_from: Option<i32>,
+) -> view_version::ActiveModel {
+    view_version::ActiveModel {
+        id: Set(format!("{}-v{}", view.id, version)),
+        view_id: Set(view.id.clone()),
+        tenant_id: Set(view.tenant_id.clone()),
+        view_code: Set(view.view_code.clone()),
+        version: Set(version),
+        view_type: Set(view.view_type.clone()),
+        view_sql: Set(view.view_sql.clone()),
+        author: Set(author),
+        restored_from: Set(restored_from),
+        created_at: Set(view.updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_of() {
+        let at = chrono::NaiveDate::from_ymd_opt(2025, 10, 21).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let view = view::Model {
+            id: "1-abc".to_string(),
+            tenant_id: "1".to_string(),
+            view_code: "sales".to_string(),
+            view_type: "comparable_card".to_string(),
+            view_sql: "SELECT 1".to_string(),
+            cache_ttl_seconds: None,
+            created_at: at,
+            updated_at: at,
+        };
+
+        // A TTL change keeps the definition
+        let ttl_changed = view::Model { cache_ttl_seconds: Some(60), ..view.clone() };
+        assert!(!is_redefined(&view, &ttl_changed));
+        let sql_changed = view::Model { view_sql: "SELECT 2".to_string(), ..view.clone() };
+        assert!(is_redefined(&view, &sql_changed));
+
+        let version = version_of(&sql_changed, 3, Some("u1".to_string()), Some(1));
+        assert_eq!(version.id, Set("1-abc-v3".to_string()));
+        assert_eq!(version.view_sql, Set("SELECT 2".to_string()));
+        assert_eq!(version.restored_from, Set(Some(1)));
+        assert_eq!(version.created_at, Set(at));
+    }
+}
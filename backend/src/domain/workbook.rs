@@ -0,0 +1,124 @@
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+use calamine::{open_workbook_from_rs, Error, Ods, Sheets, Xls, Xlsb, Xlsx};
+
+/// Spreadsheet formats accepted by the Excel imports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkbookFormat {
+    Xls,
+    Xlsx,
+    Xlsb,
+    Ods,
+}
+
+impl WorkbookFormat {
+    /// Detect the format from the file content, falling back to the file extension
+    pub fn detect(data: &[u8], file_name: Option<&str>) -> Option<Self> {
+        Self::detect_parts(data, data, file_name)
+    }
+
+    /// Detect the format from the start of a file and the part holding the zip entry
+    /// names (the central directory at the end of the file)
+    fn detect_parts(head: &[u8], entries: &[u8], file_name: Option<&str>) -> Option<Self> {
+        // Legacy .xls files are OLE2 compound documents
+        const OLE2_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+        if head.starts_with(OLE2_MAGIC) {
+            return Some(Self::Xls);
+        }
+        if head.starts_with(b"PK\x03\x04") {
+            // ODF stores its uncompressed `mimetype` entry first
+            if contains(&head[..head.len().min(128)], b"application/vnd.oasis.opendocument.spreadsheet") {
+                return Some(Self::Ods);
+            }
+            if contains(entries, b"xl/workbook.bin") {
+                return Some(Self::Xlsb);
+            }
+            if contains(entries, b"xl/workbook.xml") {
+                return Some(Self::Xlsx);
+            }
+        }
+
+        let (_, extension) = file_name?.rsplit_once('.')?;
+        match extension.to_lowercase().as_str() {
+            "xls" | "xla" => Some(Self::Xls),
+            "xlsx" | "xlsm" | "xlam" => Some(Self::Xlsx),
+            "xlsb" => Some(Self::Xlsb),
+            "ods" => Some(Self::Ods),
+            _ => None,
+        }
+    }
+}
+
+/// Open a workbook of any supported format from its content
+pub fn open_workbook<'a>(data: &'a [u8], file_name: Option<&str>) -> Result<Sheets<Cursor<&'a [u8]>>, Error> {
+    open_as(Cursor::new(data), WorkbookFormat::detect(data, file_name))
+}
+
+/// Open a workbook of any supported format from a local file, reading sheets on demand
+pub fn open_workbook_file(path: &Path, file_name: Option<&str>) -> Result<Sheets<BufReader<File>>, Error> {
+    // The zip central directory of a workbook rarely exceeds a few kilobytes
+    const TAIL_SIZE: u64 = 1 << 20;
+
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+
+    let mut head = Vec::new();
+    (&mut file).take(128).read_to_end(&mut head)?;
+    let mut tail = Vec::new();
+    file.seek(SeekFrom::Start(size.saturating_sub(TAIL_SIZE)))?;
+    file.read_to_end(&mut tail)?;
+    file.rewind()?;
+
+    let format = WorkbookFormat::detect_parts(&head, &tail, file_name.or_else(|| path.to_str()));
+    open_as(BufReader::new(file), format)
+}
+
+fn open_as<RS: Read + Seek>(reader: RS, format: Option<WorkbookFormat>) -> Result<Sheets<RS>, Error> {
+    let format = format
+        .ok_or(Error::Msg("Unsupported file format, expected xls, xlsx, xlsb or ods"))?;
+
+    Ok(match format {
+        WorkbookFormat::Xls => Sheets::Xls(open_workbook_from_rs::<Xls<_>, _>(reader).map_err(Error::Xls)?),
+        WorkbookFormat::Xlsx => Sheets::Xlsx(open_workbook_from_rs::<Xlsx<_>, _>(reader).map_err(Error::Xlsx)?),
+        WorkbookFormat::Xlsb => Sheets::Xlsb(open_workbook_from_rs::<Xlsb<_>, _>(reader).map_err(Error::Xlsb)?),
+        WorkbookFormat::Ods => Sheets::Ods(open_workbook_from_rs::<Ods<_>, _>(reader).map_err(Error::Ods)?),
+    })
+}
+
+fn contains(data: &[u8], needle: &[u8]) -> bool {
+    data.windows(needle.len()).any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::Reader;
+
+    #[test]
+    fn test_detect_format() {
+        let xls = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1, 0x00];
+        assert_eq!(WorkbookFormat::detect(&xls, Some("report.xlsx")), Some(WorkbookFormat::Xls));
+
+        let ods = b"PK\x03\x04\x14\x00\x00\x00mimetypeapplication/vnd.oasis.opendocument.spreadsheetPK";
+        assert_eq!(WorkbookFormat::detect(ods, None), Some(WorkbookFormat::Ods));
+
+        let xlsb = b"PK\x03\x04....[Content_Types].xml....xl/workbook.bin....";
+        assert_eq!(WorkbookFormat::detect(xlsb, None), Some(WorkbookFormat::Xlsb));
+
+        // Unrecognized content falls back to the extension
+        assert_eq!(WorkbookFormat::detect(b"", Some("uploads/Stores.XLS")), Some(WorkbookFormat::Xls));
+        assert_eq!(WorkbookFormat::detect(b"id,name", Some("stores.csv")), None);
+        assert_eq!(WorkbookFormat::detect(b"", None), None);
+
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        workbook.add_worksheet().set_name("门店").unwrap().write_string(0, 0, "store").unwrap();
+        let content = workbook.save_to_buffer().unwrap();
+        assert_eq!(WorkbookFormat::detect(&content, None), Some(WorkbookFormat::Xlsx));
+
+        let sheets = open_workbook(&content, Some("stores.bin")).unwrap();
+        assert_eq!(sheets.sheet_names(), vec!["门店".to_string()]);
+        assert!(open_workbook(b"not a workbook", None).is_err());
+    }
+}
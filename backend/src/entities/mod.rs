@@ -4,14 +4,20 @@ pub mod user;
 pub mod data_source;
 pub mod storage;
 pub mod view;
+pub mod view_version;
 pub mod data_table;
 pub mod data_table_column;
 pub mod data_table_usage;
+pub mod task_schedule;
+pub mod task_run;
 
 pub use user::Entity as User;
 pub use data_source::Entity as DataSource;
 pub use storage::Entity as Storage;
 pub use view::Entity as View;
+pub use view_version::Entity as ViewVersion;
 pub use data_table::Entity as DataTable;
 pub use data_table_column::Entity as DataTableColumn;
 pub use data_table_usage::Entity as DataTableUsage;
+pub use task_schedule::Entity as TaskSchedule;
+pub use task_run::Entity as TaskRun;
@@ -10,6 +10,8 @@ pub struct Model {
     pub view_code: String,
     pub view_type: String,
     pub view_sql: String,
+    // Seconds query results are cached, the configured default when unset, 0 disables
+    pub cache_ttl_seconds: Option<i32>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Definition of a view as of one change, never updated
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "view_versions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub view_id: String,
+    pub tenant_id: String,
+    pub view_code: String,
+    pub version: i32,
+    pub view_type: String,
+    pub view_sql: String,
+    // User ID of the change; unset only for the first version of views inserted without the API
+    pub author: Option<String>,
+    // Version restored by a rollback
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
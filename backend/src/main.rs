@@ -14,16 +14,19 @@ use config::AppConfig;
 use repository::{
     UserRepository, DataSourceRepository, StorageRepository, ViewRepository,
     DataTableRepository, DataTableColumnRepository, DataTableUsageRepository,
+    TaskScheduleRepository, TaskRunRepository,
 };
 use domain::{
     AuthService, DataSourceService, StorageService, 
-    TaskService, TaskCenterClient, ExecutorEngine, QueryService,
-    DataTableService, DataTableColumnService, DataTableUsageService,
+    TaskService, TaskCenterClient, TaskRunService, ExecutorEngine, ExecutorRegistry, TaskScheduler, JobService, QueryService,
+    DataTableService, DataTableColumnService, DataTableUsageService, ConnectionPools, QueryCache,
+    ViewService,
 };
 use api::{
     create_auth_routes, create_data_source_routes, create_storage_routes,
-    create_task_routes, create_executor_routes, create_query_routes, logging_middleware,
+    create_task_routes, create_executor_routes, create_job_routes, create_query_routes, logging_middleware,
     create_data_table_routes, create_data_table_column_routes, create_data_table_usage_routes,
+    create_view_routes,
 };
 use migration::{Migrator, MigratorTrait};
 
@@ -79,6 +82,12 @@ async fn main() {
     let db7 = Database::connect(&db_url)
         .await
         .expect("Failed to connect to database");
+    let db8 = Database::connect(&db_url)
+        .await
+        .expect("Failed to connect to database");
+    let db9 = Database::connect(&db_url)
+        .await
+        .expect("Failed to connect to database");
 
     // Initialize repositories
     let user_repo = Arc::new(UserRepository::new(db1));
@@ -88,6 +97,8 @@ async fn main() {
     let data_table_repo = Arc::new(DataTableRepository::new(db5));
     let data_table_column_repo = Arc::new(DataTableColumnRepository::new(db6));
     let data_table_usage_repo = Arc::new(DataTableUsageRepository::new(db7));
+    let task_schedule_repo = Arc::new(TaskScheduleRepository::new(db8));
+    let task_run_repo = Arc::new(TaskRunRepository::new(db9));
 
     // Initialize services
     let auth_service = Arc::new(AuthService::new(
@@ -96,28 +107,84 @@ async fn main() {
         app_config.jwt.expiration_hours,
     ));
 
-    let data_source_service = Arc::new(DataSourceService::new(data_source_repo.clone()));
+    // Connection pools of tenant data sources, shared by queries, task runs and uploads
+    let connection_pools = Arc::new(ConnectionPools::new(app_config.pools.clone()));
+    connection_pools.clone().start();
+
+    // Cache of view query results, dropped when the tables behind them are written
+    let query_cache = Arc::new(QueryCache::new(&app_config.cache));
+
+    let data_source_service = Arc::new(DataSourceService::new(
+        data_source_repo.clone(),
+        connection_pools.clone(),
+        query_cache.clone(),
+    ));
     let storage_service = Arc::new(StorageService::new(storage_repo));
+    let data_table_service = Arc::new(DataTableService::new(
+        data_table_repo,
+        data_table_column_repo.clone(),
+        data_table_usage_repo.clone(),
+        data_source_repo.clone(),
+        connection_pools.clone(),
+        query_cache.clone(),
+    ));
 
     let task_center_client = Arc::new(TaskCenterClient::new(
         app_config.task_center.base_url.clone()
     ));
     let task_service = Arc::new(TaskService::new(task_center_client));
+    let task_run_service = Arc::new(TaskRunService::new(task_run_repo));
+
+    // Runs queued or running before this start were lost with the previous process
+    match task_run_service.fail_interrupted(chrono::Utc::now().naive_utc()).await {
+        Ok(0) => {}
+        Ok(count) => log::warn!("Marked {} interrupted task runs as failed", count),
+        Err(e) => log::error!("Failed to mark interrupted task runs: {}", e),
+    }
+
+    // Register executors; in-house executors can be registered here as well
+    let executor_registry = ExecutorRegistry::builtin();
 
     let executor_engine = Arc::new(ExecutorEngine::new(
         data_source_service.clone(),
         storage_service.clone(),
+        data_table_service.clone(),
         task_service.clone(),
+        task_run_service.clone(),
+        executor_registry,
+        app_config.executor.clone(),
     ));
 
-    let query_service = Arc::new(QueryService::new(data_source_service.clone(), view_repo));
+    // Start the built-in cron scheduler
+    if app_config.scheduler.enabled {
+        let task_scheduler = Arc::new(TaskScheduler::new(
+            task_service.clone(),
+            executor_engine.clone(),
+            task_schedule_repo,
+            std::time::Duration::from_secs(app_config.scheduler.poll_interval_seconds.max(1)),
+        ));
+        task_scheduler.start();
+    } else {
+        log::info!("Task scheduler disabled");
+    }
 
-    let data_table_service = Arc::new(DataTableService::new(
-        data_table_repo,
-        data_table_column_repo.clone(),
-        data_table_usage_repo.clone(),
-        data_source_repo.clone(),
+    let job_service = Arc::new(JobService::new(
+        app_config.jobs.dir.clone(),
+        data_source_service.clone(),
+        executor_engine.clone(),
     ));
+
+    let query_service = Arc::new(QueryService::new(
+        data_source_service.clone(),
+        view_repo.clone(),
+        query_cache.clone(),
+    ));
+    let view_service = Arc::new(ViewService::new(
+        view_repo,
+        query_service.clone(),
+        query_cache.clone(),
+    ));
+
     let data_table_column_service = Arc::new(DataTableColumnService::new(data_table_column_repo));
     let data_table_usage_service = Arc::new(DataTableUsageService::new(data_table_usage_repo));
 
@@ -143,10 +210,21 @@ async fn main() {
         // Executor routes (with JWT protection)
         .nest("/api/v1/executor", create_executor_routes(
             executor_engine,
+            task_run_service,
+            auth_service.clone(),
+        ))
+        // Job definition routes (with JWT protection)
+        .nest("/api/v1/jobs", create_job_routes(
+            job_service,
             auth_service.clone(),
         ))
         // Query routes (no JWT protection, uses tenant_id header)
         .nest("/api/v1", create_query_routes(query_service))
+        // View management routes (no JWT protection, uses tenant_id header)
+        .nest("/api/v1/views", create_view_routes(
+            view_service,
+            auth_service.clone(),
+        ))
         // Data table routes (with JWT protection)
         .nest("/api/v1/data-tables", create_data_table_routes(data_table_service))
         // Data table column routes (no JWT protection)
@@ -7,6 +7,8 @@ pub mod view;
 pub mod data_table;
 pub mod data_table_column;
 pub mod data_table_usage;
+pub mod task_schedule;
+pub mod task_run;
 
 pub use user::UserRepository;
 pub use data_source::DataSourceRepository;
@@ -15,3 +17,5 @@ pub use view::ViewRepository;
 pub use data_table::DataTableRepository;
 pub use data_table_column::DataTableColumnRepository;
 pub use data_table_usage::DataTableUsageRepository;
+pub use task_schedule::TaskScheduleRepository;
+pub use task_run::{TaskRunRepository, TaskRunFilter};
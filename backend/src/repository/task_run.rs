@@ -0,0 +1,155 @@
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::entities::task_run::{self, Entity as TaskRun};
+
+/// Filters of the run history of a tenant
+#[derive(Debug, Clone, Default)]
+pub struct TaskRunFilter {
+    pub tenant_id: String,
+    pub task_id: Option<String>,
+    pub status: Option<String>,
+    // Inclusive range of logical dates, yyyyMMdd
+    pub logical_date_from: Option<String>,
+    pub logical_date_to: Option<String>,
+    // Range of started_at, the end exclusive
+    pub started_from: Option<chrono::NaiveDateTime>,
+    pub started_to: Option<chrono::NaiveDateTime>,
+    pub limit: u64,
+}
+
+impl TaskRunFilter {
+    /// Runs matching the filters, most recent first
+    pub fn select(&self) -> Select<TaskRun> {
+        let mut query = TaskRun::find().filter(task_run::Column::TenantId.eq(self.tenant_id.as_str()));
+
+        if let Some(task_id) = &self.task_id {
+            query = query.filter(task_run::Column::TaskId.eq(task_id.as_str()));
+        }
+        if let Some(status) = &self.status {
+            query = query.filter(task_run::Column::Status.eq(status.as_str()));
+        }
+        if let Some(from) = &self.logical_date_from {
+            query = query.filter(task_run::Column::LogicalDate.gte(from.as_str()));
+        }
+        if let Some(to) = &self.logical_date_to {
+            query = query.filter(task_run::Column::LogicalDate.lte(to.as_str()));
+        }
+        if let Some(started_from) = self.started_from {
+            query = query.filter(task_run::Column::StartedAt.gte(started_from));
+        }
+        if let Some(started_to) = self.started_to {
+            query = query.filter(task_run::Column::StartedAt.lt(started_to));
+        }
+
+        query
+            .order_by_desc(task_run::Column::StartedAt)
+            .limit(self.limit)
+    }
+}
+
+pub struct TaskRunRepository {
+    db: DatabaseConnection,
+}
+
+impl TaskRunRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, model: task_run::ActiveModel) -> Result<task_run::Model, DbErr> {
+        model.insert(&self.db).await
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<task_run::Model>, DbErr> {
+        TaskRun::find_by_id(id.to_string()).one(&self.db).await
+    }
+
+    pub async fn update(&self, model: task_run::ActiveModel) -> Result<task_run::Model, DbErr> {
+        model.update(&self.db).await
+    }
+
+    /// Move a run from `from_status` to `status`; false if it was not in `from_status`
+    pub async fn transition(
+        &self,
+        id: &str,
+        from_status: &str,
+        status: &str,
+        started_at: chrono::NaiveDateTime,
+    ) -> Result<bool, DbErr> {
+        let result = TaskRun::update_many()
+            .col_expr(task_run::Column::Status, Expr::value(status))
+            .col_expr(task_run::Column::StartedAt, Expr::value(started_at))
+            .filter(task_run::Column::Id.eq(id))
+            .filter(task_run::Column::Status.eq(from_status))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Set the status and message of runs in one of `statuses` created before `created_before`
+    pub async fn finish_all(
+        &self,
+        statuses: &[&str],
+        created_before: chrono::NaiveDateTime,
+        status: &str,
+        message: &str,
+    ) -> Result<u64, DbErr> {
+        let result = TaskRun::update_many()
+            .col_expr(task_run::Column::Status, Expr::value(status))
+            .col_expr(task_run::Column::Message, Expr::value(message))
+            .col_expr(task_run::Column::FinishedAt, Expr::value(chrono::Utc::now().naive_utc()))
+            .filter(task_run::Column::Status.is_in(statuses.iter().copied()))
+            .filter(task_run::Column::CreatedAt.lt(created_before))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    pub async fn find_filtered(&self, filter: &TaskRunFilter) -> Result<Vec<task_run::Model>, DbErr> {
+        filter.select().all(&self.db).await
+    }
+
+    /// Runs of a task in a tenant, or outside any tenant when `tenant_id` is `None`
+    pub fn runs_of(task_id: &str, tenant_id: Option<&str>) -> Select<TaskRun> {
+        let query = TaskRun::find().filter(task_run::Column::TaskId.eq(task_id));
+        match tenant_id {
+            Some(tenant_id) => query.filter(task_run::Column::TenantId.eq(tenant_id)),
+            None => query.filter(task_run::Column::TenantId.is_null()),
+        }
+    }
+
+    /// Latest run of a task, with the given status and logical date if any
+    pub async fn find_latest(
+        &self,
+        task_id: &str,
+        tenant_id: Option<&str>,
+        status: Option<&str>,
+        logical_date: Option<&str>,
+    ) -> Result<Option<task_run::Model>, DbErr> {
+        let mut query = Self::runs_of(task_id, tenant_id);
+        if let Some(status) = status {
+            query = query.filter(task_run::Column::Status.eq(status));
+        }
+        if let Some(logical_date) = logical_date {
+            query = query.filter(task_run::Column::LogicalDate.eq(logical_date));
+        }
+        query
+            .order_by_desc(task_run::Column::StartedAt)
+            .one(&self.db)
+            .await
+    }
+
+    /// Whether a task has a run for a logical date later than the given one
+    pub async fn exists_later(
+        &self,
+        task_id: &str,
+        tenant_id: Option<&str>,
+        logical_date: &str,
+    ) -> Result<bool, DbErr> {
+        Ok(Self::runs_of(task_id, tenant_id)
+            .filter(task_run::Column::LogicalDate.gt(logical_date))
+            .one(&self.db)
+            .await?
+            .is_some())
+    }
+}
@@ -0,0 +1,47 @@
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::entities::task_schedule::{self, Entity as TaskSchedule};
+
+pub struct TaskScheduleRepository {
+    db: DatabaseConnection,
+}
+
+impl TaskScheduleRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, model: task_schedule::ActiveModel) -> Result<task_schedule::Model, DbErr> {
+        model.insert(&self.db).await
+    }
+
+    pub async fn find_by_task_id(&self, task_id: &str) -> Result<Option<task_schedule::Model>, DbErr> {
+        TaskSchedule::find_by_id(task_id.to_string()).one(&self.db).await
+    }
+
+    pub async fn update(&self, model: task_schedule::ActiveModel) -> Result<task_schedule::Model, DbErr> {
+        model.update(&self.db).await
+    }
+
+    /// Move the last fire time of a task from `last_fired_at` to `fired_at`.
+    ///
+    /// Returns false when another instance has already moved it, so that each
+    /// fire time is claimed by a single instance.
+    pub async fn claim_fire_time(
+        &self,
+        task_id: &str,
+        last_fired_at: chrono::NaiveDateTime,
+        fired_at: chrono::NaiveDateTime,
+        logical_date: &str,
+    ) -> Result<bool, DbErr> {
+        let result = TaskSchedule::update_many()
+            .col_expr(task_schedule::Column::LastFiredAt, Expr::value(fired_at))
+            .col_expr(task_schedule::Column::LastLogicalDate, Expr::value(logical_date))
+            .col_expr(task_schedule::Column::UpdatedAt, Expr::value(chrono::Utc::now().naive_utc()))
+            .filter(task_schedule::Column::TaskId.eq(task_id))
+            .filter(task_schedule::Column::LastFiredAt.eq(last_fired_at))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+}
@@ -1,5 +1,6 @@
 use sea_orm::*;
 use crate::entities::view::{self, Entity as View};
+use crate::entities::view_version::{self, Entity as ViewVersion};
 
 pub struct ViewRepository {
     db: DatabaseConnection,
@@ -10,6 +11,46 @@ impl ViewRepository {
         Self { db }
     }
 
+    /// Insert a view together with its first version
+    pub async fn create(
+        &self,
+        model: view::ActiveModel,
+        version: view_version::ActiveModel,
+    ) -> Result<view::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        let view = model.insert(&txn).await?;
+        version.insert(&txn).await?;
+        txn.commit().await?;
+        Ok(view)
+    }
+
+    /// Update a view and record the versions of the change in the same transaction
+    pub async fn update(
+        &self,
+        model: view::ActiveModel,
+        versions: Vec<view_version::ActiveModel>,
+    ) -> Result<view::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        let view = model.update(&txn).await?;
+        for version in versions {
+            version.insert(&txn).await?;
+        }
+        txn.commit().await?;
+        Ok(view)
+    }
+
+    /// Delete a view and its history
+    pub async fn delete(&self, id: &str) -> Result<DeleteResult, DbErr> {
+        let txn = self.db.begin().await?;
+        ViewVersion::delete_many()
+            .filter(view_version::Column::ViewId.eq(id))
+            .exec(&txn)
+            .await?;
+        let result = View::delete_by_id(id.to_string()).exec(&txn).await?;
+        txn.commit().await?;
+        Ok(result)
+    }
+
     pub async fn find_by_code_and_tenant(
         &self,
         view_code: &str,
@@ -28,4 +69,33 @@ impl ViewRepository {
             .all(&self.db)
             .await
     }
+
+    /// Versions of a view, most recent first
+    pub async fn find_versions(&self, view_id: &str) -> Result<Vec<view_version::Model>, DbErr> {
+        ViewVersion::find()
+            .filter(view_version::Column::ViewId.eq(view_id))
+            .order_by_desc(view_version::Column::Version)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_version(
+        &self,
+        view_id: &str,
+        version: i32,
+    ) -> Result<Option<view_version::Model>, DbErr> {
+        ViewVersion::find()
+            .filter(view_version::Column::ViewId.eq(view_id))
+            .filter(view_version::Column::Version.eq(version))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn find_latest_version(&self, view_id: &str) -> Result<Option<view_version::Model>, DbErr> {
+        ViewVersion::find()
+            .filter(view_version::Column::ViewId.eq(view_id))
+            .order_by_desc(view_version::Column::Version)
+            .one(&self.db)
+            .await
+    }
 }